@@ -0,0 +1,97 @@
+use anyhow::Result;
+use clap::{Args, Parser, Subcommand};
+use data::{
+    sources::{AlpacaBarSource, ScheduledBarSource},
+    Config, IngestOptions,
+};
+use database::SqliteDb;
+
+use crate::ingest::AdjustmentArg;
+use crate::timeframe::{resolve_timeframe, TimeframeArg};
+
+#[derive(Subcommand)]
+pub enum CheckCommands {
+    /// List the trading sessions with no stored bar, per symbol
+    Gaps(GapsArgs),
+}
+
+#[derive(Args)]
+pub struct GapsArgs {
+    #[arg(long, value_enum, required_unless_present = "table")]
+    pub timeframe: Option<TimeframeArg>,
+    /// Bar table to check, e.g. hourly_stock_bars, instead of --timeframe
+    #[arg(long, conflicts_with = "timeframe")]
+    pub table: Option<String>,
+    /// Defaults to every symbol in the table
+    #[arg(long, value_delimiter = ',')]
+    pub symbols: Vec<String>,
+    /// Re-fetch the missing ranges from Alpaca and store them
+    #[arg(long)]
+    pub refetch: bool,
+    /// Corporate-action adjustment of the re-fetched prices
+    #[arg(long, value_enum, default_value = "raw")]
+    pub adjustment: AdjustmentArg,
+    /// Defaults to database.uri from the config
+    #[arg(long)]
+    pub db: Option<String>,
+}
+
+#[derive(Parser)]
+pub struct CheckArgs {
+    #[command(subcommand)]
+    pub subcommand: CheckCommands,
+}
+
+pub async fn run(args: &CheckArgs, config: &Config) -> Result<()> {
+    match &args.subcommand {
+        CheckCommands::Gaps(args) => check_gaps(args, config).await,
+    }
+}
+
+async fn check_gaps(args: &GapsArgs, config: &Config) -> Result<()> {
+    let timeframe = resolve_timeframe(args.timeframe, args.table.as_deref())?;
+    let db = SqliteDb::connect(&config.database_uri(args.db.as_deref())?).await?;
+    let symbols: Vec<String> = args.symbols.iter().map(|s| s.to_uppercase()).collect();
+
+    let gaps = data::find_gaps(&db, timeframe, &symbols).await?;
+    for gap in &gaps {
+        println!("{}", gap);
+    }
+    println!(
+        "{} gaps, {} missing sessions in {}",
+        gaps.len(),
+        gaps.iter().map(|gap| gap.sessions).sum::<usize>(),
+        timeframe.table()
+    );
+
+    if !args.refetch {
+        return Ok(());
+    }
+
+    let source = ScheduledBarSource::new(AlpacaBarSource, &config.rate_limit);
+    for gap in &gaps {
+        let (store_from, end) = gap.refetch_range(timeframe);
+        println!(
+            "Re-fetching {} from {} to {}",
+            gap.stock_symbol, store_from, end
+        );
+        let options = IngestOptions {
+            start: store_from.to_string(),
+            end: Some(end.to_string()),
+            catch_up: false,
+            adjustment: args.adjustment.into(),
+            store_from: Some(store_from),
+            feature_spec: config.feature_spec(timeframe)?,
+        };
+        data::insert_stock_bars(
+            &db,
+            &source,
+            timeframe,
+            vec![gap.stock_symbol.as_str()],
+            &options,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
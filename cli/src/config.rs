@@ -0,0 +1,24 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use data::Config;
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Print the effective settings of the config file with environment overrides applied
+    Show,
+}
+
+#[derive(Parser)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub subcommand: ConfigCommands,
+}
+
+pub async fn run(args: &ConfigArgs, config: &Config) -> Result<()> {
+    match &args.subcommand {
+        ConfigCommands::Show => {
+            print!("{}", config.to_toml()?);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use data::Config;
+use database::SqliteDb;
+
+#[derive(Subcommand)]
+pub enum CorporateActionsCommands {
+    /// Load splits and dividends from a CSV or JSON file
+    Load {
+        path: PathBuf,
+        /// Defaults to database.uri from the config
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// List the stored actions of a symbol
+    List {
+        symbol: String,
+        /// Defaults to database.uri from the config
+        #[arg(long)]
+        db: Option<String>,
+    },
+}
+
+#[derive(Parser)]
+pub struct CorporateActionsArgs {
+    #[command(subcommand)]
+    pub subcommand: CorporateActionsCommands,
+}
+
+pub async fn run(args: &CorporateActionsArgs, config: &Config) -> Result<()> {
+    match &args.subcommand {
+        CorporateActionsCommands::Load { path, db } => {
+            let actions = data::load_corporate_actions(path)?;
+            let db = SqliteDb::connect(&config.database_uri(db.as_deref())?).await?;
+            let rows = db.upsert_corporate_actions(&actions).await?;
+            println!(
+                "Loaded {} corporate actions ({} rows written)",
+                actions.len(),
+                rows
+            );
+            Ok(())
+        }
+        CorporateActionsCommands::List { symbol, db } => {
+            let db = SqliteDb::connect(&config.database_uri(db.as_deref())?).await?;
+            for action in db.corporate_actions(&symbol.to_uppercase()).await? {
+                println!(
+                    "{} {:?} split_ratio={:?} cash_amount={:?}",
+                    action.ex_date, action.action_type, action.split_ratio, action.cash_amount
+                );
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use clap::Parser;
+use data::Config;
+use database::{SqliteDb, Timeframe};
+
+/// Rank the daily bars of the whole universe against each other, day by day
+#[derive(Parser)]
+pub struct CrossSectionalArgs {
+    /// First day to recompute (YYYY-MM-DD), defaults to the latest stored day
+    #[arg(long)]
+    pub from: Option<NaiveDate>,
+    /// Defaults to database.uri from the config
+    #[arg(long)]
+    pub db: Option<String>,
+}
+
+pub async fn run(args: &CrossSectionalArgs, config: &Config) -> Result<()> {
+    let db = SqliteDb::connect(&config.database_uri(args.db.as_deref())?).await?;
+    let spec = config.feature_spec(Timeframe::Daily)?;
+    let rows = data::update_cross_sectional_features(&db, &spec, args.from).await?;
+    println!("Stored {} cross-sectional feature rows", rows);
+    Ok(())
+}
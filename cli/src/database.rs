@@ -1,14 +1,74 @@
 use anyhow::Result;
 
-use clap::{Parser, Subcommand};
-use database::SqliteDb;
-// use database::DB;
+use clap::{Args, Parser, Subcommand};
+use data::Config;
+use database::{BarRepository, SqliteDb, Timeframe};
+
+use crate::timeframe::TimeframeArg;
 
 #[derive(Subcommand)]
 pub enum DatabaseCommands {
-    CreateDatabase { uri: String },
-    TestConnection { uri: String },
-    ResetDatabase { uri: String },
+    CreateDatabase {
+        #[command(flatten)]
+        uri: UriArg,
+    },
+    TestConnection {
+        #[command(flatten)]
+        uri: UriArg,
+    },
+    ResetDatabase {
+        #[command(flatten)]
+        uri: UriArg,
+    },
+    /// Print the CREATE TABLE statement derived from a timeframe's feature spec
+    FeatureSchema {
+        #[arg(long, value_enum)]
+        timeframe: TimeframeArg,
+    },
+    /// Add feature columns missing from a timeframe's table
+    SyncFeatures {
+        /// Defaults to database.uri from the config
+        #[arg(long)]
+        uri: Option<String>,
+        #[arg(long, value_enum)]
+        timeframe: TimeframeArg,
+    },
+    /// Apply the pending schema migrations
+    Migrate {
+        /// Defaults to database.uri from the config
+        #[arg(long)]
+        uri: Option<String>,
+    },
+    /// List applied and pending schema migrations
+    Status {
+        /// Defaults to database.uri from the config
+        #[arg(long)]
+        uri: Option<String>,
+    },
+    /// Revert the latest applied schema migration
+    Revert {
+        /// Defaults to database.uri from the config
+        #[arg(long)]
+        uri: Option<String>,
+    },
+}
+
+/// Database URI of the subcommands that took it as a positional argument
+/// before the others took `--uri`, so both forms work.
+#[derive(Args)]
+pub struct UriArg {
+    /// Defaults to database.uri from the config
+    #[arg(conflicts_with = "uri_flag")]
+    uri: Option<String>,
+    /// Same as the positional URI
+    #[arg(long = "uri", value_name = "URI")]
+    uri_flag: Option<String>,
+}
+
+impl UriArg {
+    fn get(&self) -> Option<&str> {
+        self.uri.as_deref().or(self.uri_flag.as_deref())
+    }
 }
 
 #[derive(Parser)]
@@ -17,19 +77,90 @@ pub struct DatabaseArgs {
     pub subcommand: DatabaseCommands,
 }
 
-pub async fn run(args: &DatabaseArgs) -> Result<()> {
+pub async fn run(args: &DatabaseArgs, config: &Config) -> Result<()> {
     match &args.subcommand {
         DatabaseCommands::CreateDatabase { uri } => {
-            SqliteDb::create_new(uri).await?;
+            SqliteDb::create_new(&config.database_uri(uri.get())?).await?;
             Ok(())
         }
         DatabaseCommands::TestConnection { uri } => {
-            let db = SqliteDb::connect(uri).await?;
+            let db = SqliteDb::connect(&config.database_uri(uri.get())?).await?;
             db.test_connection().await;
             Ok(())
         }
         DatabaseCommands::ResetDatabase { uri } => {
-            SqliteDb::reset_database(uri).await?;
+            SqliteDb::reset_database(&config.database_uri(uri.get())?).await?;
+            Ok(())
+        }
+        DatabaseCommands::FeatureSchema { timeframe } => {
+            let timeframe = Timeframe::from(*timeframe);
+            println!(
+                "{}",
+                config
+                    .feature_spec(timeframe)?
+                    .create_table_sql(timeframe.table())
+            );
+            Ok(())
+        }
+        DatabaseCommands::SyncFeatures { uri, timeframe } => {
+            let timeframe = Timeframe::from(*timeframe);
+            let db = SqliteDb::connect(&config.database_uri(uri.as_deref())?).await?;
+            let statements = db
+                .sync_feature_columns(timeframe, &config.feature_spec(timeframe)?)
+                .await?;
+            if statements.is_empty() {
+                println!("{} is up to date", timeframe.table());
+            }
+            for statement in statements {
+                println!("{}", statement);
+            }
+            Ok(())
+        }
+        DatabaseCommands::Migrate { uri } => {
+            let db = SqliteDb::connect(&config.database_uri(uri.as_deref())?).await?;
+            let applied = db.migrate().await?;
+            if applied.is_empty() {
+                println!("Database is up to date");
+            }
+            for migration in applied {
+                println!(
+                    "Applied migration {} {}",
+                    migration.version, migration.description
+                );
+            }
+            Ok(())
+        }
+        DatabaseCommands::Status { uri } => {
+            let db = SqliteDb::connect(&config.database_uri(uri.as_deref())?).await?;
+            for migration in db.migration_status().await? {
+                let state = match (migration.applied, migration.checksum_mismatch) {
+                    (true, true) => "applied (changed since)",
+                    (true, false) => "applied",
+                    (false, _) => "pending",
+                };
+                println!(
+                    "{} {:<40} {}{}",
+                    migration.version,
+                    migration.description,
+                    state,
+                    if migration.reversible {
+                        ", reversible"
+                    } else {
+                        ""
+                    }
+                );
+            }
+            Ok(())
+        }
+        DatabaseCommands::Revert { uri } => {
+            let db = SqliteDb::connect(&config.database_uri(uri.as_deref())?).await?;
+            match db.revert_migration().await? {
+                Some(migration) => println!(
+                    "Reverted migration {} {}",
+                    migration.version, migration.description
+                ),
+                None => println!("No applied migrations to revert"),
+            }
             Ok(())
         }
     }
@@ -0,0 +1,380 @@
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use arrow::ipc::writer::{FileWriter, StreamWriter};
+use chrono::{DateTime, Datelike, Duration, NaiveDate};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use data::{calendar, Config};
+use database::{
+    stock_bars_record_batch, stock_bars_schema, FeatureSpec, SqliteDb, StockBarModel,
+    StockBarsQuery, Timeframe,
+};
+use futures_util::TryStreamExt;
+use parquet::{
+    arrow::ArrowWriter,
+    basic::{Compression, ZstdLevel},
+    file::properties::WriterProperties,
+};
+
+use crate::timeframe::{resolve_timeframe, TimeframeArg};
+
+#[derive(Subcommand)]
+pub enum ExportCommands {
+    /// Write the bars of a timeframe to a CSV file
+    Csv {
+        #[command(flatten)]
+        filter: BarFilterArgs,
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Write the bars of a table to typed Parquet files in a directory
+    Parquet {
+        #[command(flatten)]
+        filter: BarFilterArgs,
+        #[arg(long)]
+        out: PathBuf,
+        /// Write one Parquet file per symbol or per year instead of a single file
+        #[arg(long, value_enum)]
+        partition_by: Option<Partition>,
+    },
+    /// Write the bars of a table to an Arrow IPC file (Feather v2)
+    Arrow {
+        #[command(flatten)]
+        filter: BarFilterArgs,
+        #[arg(long)]
+        out: PathBuf,
+        /// Write the IPC streaming format instead of the file format
+        #[arg(long)]
+        stream: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Partition {
+    Symbol,
+    Year,
+}
+
+#[derive(Parser)]
+pub struct ExportArgs {
+    #[command(subcommand)]
+    pub subcommand: ExportCommands,
+}
+
+/// Which bars to export.
+#[derive(Args)]
+pub struct BarFilterArgs {
+    #[arg(long, value_enum, required_unless_present = "table")]
+    pub timeframe: Option<TimeframeArg>,
+    /// Bar table to export, e.g. daily_stock_bars, instead of --timeframe
+    #[arg(long, conflicts_with = "timeframe")]
+    pub table: Option<String>,
+    /// Defaults to every symbol in the table
+    #[arg(long, value_delimiter = ',')]
+    pub symbols: Vec<String>,
+    /// Only symbols of this sector, e.g. "Energy"
+    #[arg(long)]
+    pub sector: Option<String>,
+    /// First day to export, inclusive (YYYY-MM-DD)
+    #[arg(long)]
+    pub from: Option<NaiveDate>,
+    /// Last day to export, inclusive (YYYY-MM-DD)
+    #[arg(long)]
+    pub to: Option<NaiveDate>,
+    /// Leave out bars whose indicators are still warming up
+    #[arg(long)]
+    pub skip_warm_up: bool,
+    /// Defaults to database.uri from the config
+    #[arg(long)]
+    pub uri: Option<String>,
+}
+
+impl BarFilterArgs {
+    pub fn timeframe(&self) -> Result<Timeframe> {
+        resolve_timeframe(self.timeframe, self.table.as_deref())
+    }
+
+    pub async fn connect(&self, config: &Config) -> Result<SqliteDb> {
+        SqliteDb::connect(&config.database_uri(self.uri.as_deref())?).await
+    }
+
+    /// Bars to export, with the configured extra feature columns.
+    pub fn query(&self, config: &Config) -> Result<StockBarsQuery> {
+        let timeframe = self.timeframe()?;
+        let symbols: Vec<&str> = self.symbols.iter().map(String::as_str).collect();
+        let mut query = StockBarsQuery::new(timeframe)
+            .symbols(&symbols)
+            .features(config.feature_spec(timeframe)?);
+        if let Some(sector) = &self.sector {
+            query = query.sector(sector);
+        }
+        // Days are exchange days, bars are stamped in UTC
+        if let Some(from) = self.from {
+            query = query.start(calendar::exchange_midnight(from));
+        }
+        if let Some(to) = self.to {
+            let next_day = to
+                .succ_opt()
+                .ok_or_else(|| anyhow!("Invalid end date: {}", to))?;
+            query = query.end(calendar::exchange_midnight(next_day) - Duration::milliseconds(1));
+        }
+        if self.skip_warm_up {
+            query = query.skip_warm_up();
+        }
+        Ok(query)
+    }
+}
+
+pub async fn run(args: &ExportArgs, config: &Config) -> Result<()> {
+    match &args.subcommand {
+        ExportCommands::Csv { filter, out } => export_csv(filter, out, config).await,
+        ExportCommands::Parquet {
+            filter,
+            out,
+            partition_by,
+        } => export_parquet(filter, out, *partition_by, config).await,
+        ExportCommands::Arrow {
+            filter,
+            out,
+            stream,
+        } => export_arrow(filter, out, *stream, config).await,
+    }
+}
+
+async fn export_csv(filter: &BarFilterArgs, out: &Path, config: &Config) -> Result<()> {
+    let db = filter.connect(config).await?;
+    let mut query = filter.query(config)?;
+    let spec = query.feature_spec();
+    let feature_columns = spec.columns();
+
+    let mut writer = csv::Writer::from_path(out)?;
+    writer.write_record(spec.table_columns())?;
+
+    let mut bars = query.stream(&db)?;
+    let mut rows = 0;
+    while let Some(bar) = bars.try_next().await? {
+        writer.write_record(csv_record(&bar, &feature_columns))?;
+        rows += 1;
+    }
+    writer.flush()?;
+
+    println!("Exported {} rows to {}", rows, out.display());
+    Ok(())
+}
+
+/// Core columns followed by the features, with an empty field for NULLs.
+fn csv_record(bar: &StockBarModel, feature_columns: &[&str]) -> Vec<String> {
+    let mut record = vec![
+        bar.event_datetime.clone(),
+        bar.event_unix_timestamp.to_string(),
+        bar.open_price.to_string(),
+        bar.close_price.to_string(),
+        bar.high_price.to_string(),
+        bar.low_price.to_string(),
+        bar.volume.to_string(),
+        bar.volume_weighted_price.to_string(),
+        bar.stock_symbol.clone(),
+        bar.timeframe.clone(),
+        bar.bar_trend.clone(),
+        bar.buy_or_sell.to_string(),
+        bar.next_period_price.to_string(),
+        bar.next_period_trend.clone(),
+        bar.next_period_unix_timestamp.to_string(),
+        bar.next_period_event_datetime.clone(),
+        bar.previous_period_trend.clone(),
+        bar.adjustment_mode.clone(),
+        bar.provenance.clone(),
+    ];
+    record.extend(feature_columns.iter().map(|column| {
+        bar.feature(column)
+            .map(|value| value.to_string())
+            .unwrap_or_default()
+    }));
+    record
+}
+
+async fn export_arrow(
+    filter: &BarFilterArgs,
+    out: &Path,
+    stream: bool,
+    config: &Config,
+) -> Result<()> {
+    let db = filter.connect(config).await?;
+    let mut query = filter.query(config)?;
+    let mut bars = db.bars_as_arrow(&mut query)?;
+    let file = File::create(out)?;
+
+    let mut rows = 0;
+    if stream {
+        let mut writer = StreamWriter::try_new(file, &bars.schema)?;
+        while let Some(batch) = bars.batches.try_next().await? {
+            rows += batch.num_rows();
+            writer.write(&batch)?;
+        }
+        writer.finish()?;
+    } else {
+        let mut writer = FileWriter::try_new(file, &bars.schema)?;
+        while let Some(batch) = bars.batches.try_next().await? {
+            rows += batch.num_rows();
+            writer.write(&batch)?;
+        }
+        writer.finish()?;
+    }
+
+    println!("Exported {} rows to {}", rows, out.display());
+    Ok(())
+}
+
+/// Rows buffered per Parquet file before they are written out as a row group.
+const PARQUET_BATCH_ROWS: usize = 65_536;
+
+async fn export_parquet(
+    filter: &BarFilterArgs,
+    out: &Path,
+    partition_by: Option<Partition>,
+    config: &Config,
+) -> Result<()> {
+    let db = filter.connect(config).await?;
+    let mut query = filter.query(config)?;
+    if matches!(partition_by, Some(Partition::Year)) {
+        // One year's file is open at a time, as with symbols
+        query = query.by_time();
+    }
+    std::fs::create_dir_all(out)?;
+
+    let mut writer =
+        PartitionedParquetWriter::new(out, query.timeframe(), query.feature_spec(), partition_by);
+    let mut bars = query.stream(&db)?;
+    let mut rows = 0;
+    while let Some(bar) = bars.try_next().await? {
+        writer.write(bar)?;
+        rows += 1;
+    }
+    let files = writer.close()?;
+
+    println!(
+        "Exported {} rows to {} Parquet files in {}",
+        rows,
+        files,
+        out.display()
+    );
+    Ok(())
+}
+
+struct ParquetPartition {
+    key: String,
+    writer: ArrowWriter<File>,
+    buffer: Vec<StockBarModel>,
+}
+
+/// Streams bars into Parquet files, keeping at most one batch of rows in
+/// memory. Bars arrive grouped by partition, ordered by symbol or by time when
+/// partitioning by year, so a partition's file is closed as soon as the next
+/// partition starts.
+struct PartitionedParquetWriter {
+    out: PathBuf,
+    table: &'static str,
+    spec: FeatureSpec,
+    partition_by: Option<Partition>,
+    current: Option<ParquetPartition>,
+    files: usize,
+}
+
+impl PartitionedParquetWriter {
+    fn new(
+        out: &Path,
+        timeframe: Timeframe,
+        spec: FeatureSpec,
+        partition_by: Option<Partition>,
+    ) -> Self {
+        Self {
+            out: out.to_path_buf(),
+            table: timeframe.table(),
+            spec,
+            partition_by,
+            current: None,
+            files: 0,
+        }
+    }
+
+    fn write(&mut self, bar: StockBarModel) -> Result<()> {
+        let key = self.partition_key(&bar)?;
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|partition| partition.key != key)
+        {
+            self.close_current()?;
+        }
+
+        let partition = match self.current.take() {
+            Some(partition) => partition,
+            None => self.open(key)?,
+        };
+        let partition = self.current.insert(partition);
+
+        partition.buffer.push(bar);
+        if partition.buffer.len() >= PARQUET_BATCH_ROWS {
+            flush(&self.spec, partition)?;
+        }
+        Ok(())
+    }
+
+    /// Hive-style directory of the bar's partition, empty when unpartitioned.
+    fn partition_key(&self, bar: &StockBarModel) -> Result<String> {
+        Ok(match self.partition_by {
+            None => String::new(),
+            Some(Partition::Symbol) => format!("symbol={}", bar.stock_symbol),
+            Some(Partition::Year) => {
+                let event = DateTime::from_timestamp_millis(bar.event_unix_timestamp)
+                    .ok_or_else(|| anyhow!("Invalid timestamp: {}", bar.event_unix_timestamp))?;
+                format!("year={}", calendar::exchange_date(event).year())
+            }
+        })
+    }
+
+    fn open(&mut self, key: String) -> Result<ParquetPartition> {
+        let dir = self.out.join(&key);
+        std::fs::create_dir_all(&dir)?;
+        let file = File::create(dir.join(format!("{}.parquet", self.table)))?;
+
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .build();
+        let writer = ArrowWriter::try_new(file, stock_bars_schema(&self.spec), Some(properties))?;
+        self.files += 1;
+
+        Ok(ParquetPartition {
+            key,
+            writer,
+            buffer: Vec::with_capacity(PARQUET_BATCH_ROWS),
+        })
+    }
+
+    fn close_current(&mut self) -> Result<()> {
+        if let Some(mut partition) = self.current.take() {
+            flush(&self.spec, &mut partition)?;
+            partition.writer.close()?;
+        }
+        Ok(())
+    }
+
+    /// Finishes the last file and returns how many were written.
+    fn close(mut self) -> Result<usize> {
+        self.close_current()?;
+        Ok(self.files)
+    }
+}
+
+fn flush(spec: &FeatureSpec, partition: &mut ParquetPartition) -> Result<()> {
+    if partition.buffer.is_empty() {
+        return Ok(());
+    }
+    let batch = stock_bars_record_batch(spec, &partition.buffer)?;
+    partition.writer.write(&batch)?;
+    partition.buffer.clear();
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, Result};
+use chrono_tz::Tz;
+use clap::{Parser, Subcommand};
+use data::{
+    sources::{CsvMapping, VendorCsvSource},
+    Config, IngestOptions,
+};
+use database::{AdjustmentMode, SqliteDb};
+
+use crate::timeframe::TimeframeArg;
+
+#[derive(Subcommand)]
+pub enum ImportCommands {
+    /// Import a vendor OHLCV CSV through the feature pipeline
+    Csv {
+        path: PathBuf,
+        #[arg(long, value_enum)]
+        timeframe: TimeframeArg,
+        /// Column holding the symbol
+        #[arg(long)]
+        symbol_column: Option<String>,
+        /// Symbol of every row, for single-symbol files
+        #[arg(long)]
+        symbol: Option<String>,
+        /// Column of each bar field as field=column pairs, e.g. timestamp=Date,vwap=VWAP.
+        /// Unmapped fields are read from the column named after the field
+        #[arg(long, value_delimiter = ',')]
+        mapping: Vec<String>,
+        /// chrono format of the timestamp column, `unix` or `unix_ms` (default RFC 3339)
+        #[arg(long)]
+        timestamp_format: Option<String>,
+        /// IANA timezone of timestamps without an offset
+        #[arg(long, default_value = "UTC")]
+        timezone: String,
+        /// Import the valid lines even when some lines are invalid
+        #[arg(long)]
+        skip_invalid: bool,
+        /// Defaults to database.uri from the config
+        #[arg(long)]
+        db: Option<String>,
+    },
+}
+
+#[derive(Parser)]
+pub struct ImportArgs {
+    #[command(subcommand)]
+    pub subcommand: ImportCommands,
+}
+
+pub async fn run(args: &ImportArgs, config: &Config) -> Result<()> {
+    match &args.subcommand {
+        ImportCommands::Csv {
+            path,
+            timeframe,
+            symbol_column,
+            symbol,
+            mapping,
+            timestamp_format,
+            timezone,
+            skip_invalid,
+            db,
+        } => {
+            let mut csv_mapping = CsvMapping {
+                symbol_column: symbol_column.clone(),
+                symbol: symbol.clone(),
+                timestamp_format: timestamp_format.clone(),
+                timezone: timezone
+                    .parse::<Tz>()
+                    .map_err(|e| anyhow!("Unknown timezone {}: {}", timezone, e))?,
+                ..CsvMapping::default()
+            };
+            for pair in mapping {
+                let (field, column) = pair
+                    .split_once('=')
+                    .ok_or_else(|| anyhow!("Mapping {} is not field=column", pair))?;
+                csv_mapping.map(field.trim(), column.trim())?;
+            }
+
+            let (source, errors) = VendorCsvSource::from_path(path, &csv_mapping)?;
+            for error in &errors {
+                eprintln!("{}: {}", path.display(), error);
+            }
+            if !errors.is_empty() && !skip_invalid {
+                bail!(
+                    "{} invalid lines, nothing was imported (use --skip-invalid to import the rest)",
+                    errors.len()
+                );
+            }
+
+            let Some(start) = source.first_day() else {
+                println!("No bars to import");
+                return Ok(());
+            };
+            let db = SqliteDb::connect(&config.database_uri(db.as_deref())?).await?;
+            let timeframe = (*timeframe).into();
+            let options = IngestOptions {
+                start,
+                end: None,
+                catch_up: false,
+                adjustment: AdjustmentMode::Raw,
+                store_from: None,
+                feature_spec: config.feature_spec(timeframe)?,
+            };
+            data::insert_stock_bars(&db, &source, timeframe, source.symbols(), &options).await
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+
+use chrono::NaiveDate;
+use clap::{Parser, ValueEnum};
+use data::{
+    sources::{AlpacaBarSource, ReplayBarSource, ScheduledBarSource},
+    Config, IngestOptions,
+};
+use database::{AdjustmentMode, SqliteDb};
+
+use crate::timeframe::TimeframeArg;
+
+#[derive(Parser)]
+pub struct IngestArgs {
+    #[arg(long, value_enum)]
+    pub timeframe: TimeframeArg,
+    #[arg(long, value_delimiter = ',', required_unless_present = "watchlist")]
+    pub symbols: Vec<String>,
+    /// Ingest the symbols of a stored watchlist instead of --symbols, or of a
+    /// watchlist from the config when none is stored under that name
+    #[arg(long, conflicts_with = "symbols")]
+    pub watchlist: Option<String>,
+    /// Defaults to ingest.start from the config
+    #[arg(long)]
+    pub start: Option<String>,
+    #[arg(long)]
+    pub end: Option<String>,
+    /// Only fetch bars newer than the latest stored bar of each symbol
+    #[arg(long)]
+    pub catch_up: bool,
+    /// Corporate-action adjustment of the stored prices
+    #[arg(long, value_enum, default_value = "raw")]
+    pub adjustment: AdjustmentArg,
+    /// Replay recorded responses from this directory instead of calling Alpaca
+    #[arg(long)]
+    pub replay_dir: Option<PathBuf>,
+    /// Skip recomputing the sector-relative features after loading
+    #[arg(long)]
+    pub skip_relative_features: bool,
+    /// Defaults to database.uri from the config
+    #[arg(long)]
+    pub db: Option<String>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum AdjustmentArg {
+    Raw,
+    Split,
+    TotalReturn,
+}
+
+impl From<AdjustmentArg> for AdjustmentMode {
+    fn from(adjustment: AdjustmentArg) -> Self {
+        match adjustment {
+            AdjustmentArg::Raw => AdjustmentMode::Raw,
+            AdjustmentArg::Split => AdjustmentMode::Split,
+            AdjustmentArg::TotalReturn => AdjustmentMode::TotalReturn,
+        }
+    }
+}
+
+pub async fn run(args: &IngestArgs, config: &Config) -> Result<()> {
+    let db = SqliteDb::connect(&config.database_uri(args.db.as_deref())?).await?;
+    let symbols = match &args.watchlist {
+        Some(watchlist) => watchlist_symbols(&db, config, watchlist).await?,
+        None => args.symbols.clone(),
+    };
+    let symbols: Vec<&str> = symbols.iter().map(String::as_str).collect();
+
+    let timeframe = args.timeframe.into();
+    let options = IngestOptions {
+        start: args
+            .start
+            .clone()
+            .unwrap_or_else(|| config.ingest.start.clone()),
+        end: args.end.clone(),
+        catch_up: args.catch_up,
+        adjustment: args.adjustment.into(),
+        store_from: None,
+        feature_spec: config.feature_spec(timeframe)?,
+    };
+
+    match &args.replay_dir {
+        Some(replay_dir) => {
+            let source = ReplayBarSource::new(replay_dir);
+            data::insert_stock_bars(&db, &source, timeframe, symbols.clone(), &options).await?
+        }
+        None => {
+            let source = ScheduledBarSource::new(AlpacaBarSource, &config.rate_limit);
+            data::insert_stock_bars(&db, &source, timeframe, symbols.clone(), &options).await?
+        }
+    }
+
+    if !args.skip_relative_features {
+        // Catching up continues from each symbol's latest stored features instead
+        let from = if args.catch_up {
+            None
+        } else {
+            let start = options.start.get(..10).unwrap_or(&options.start);
+            Some(
+                NaiveDate::parse_from_str(start, "%Y-%m-%d")
+                    .with_context(|| format!("Invalid start date: {}", options.start))?,
+            )
+        };
+        let rows = data::update_relative_features(&db, timeframe, &symbols, from).await?;
+        println!("Stored {} sector-relative feature rows", rows);
+    }
+    Ok(())
+}
+
+/// Symbols of a stored watchlist, falling back to the config's watchlists.
+async fn watchlist_symbols(db: &SqliteDb, config: &Config, name: &str) -> Result<Vec<String>> {
+    let stored = db.watchlists().await?;
+    if stored.iter().any(|watchlist| watchlist.name == name) {
+        return db.watchlist_symbols(name).await;
+    }
+    match config.watchlists.get(name) {
+        Some(symbols) => Ok(symbols.clone()),
+        None => bail!(
+            "Unknown watchlist: {}. Store it with `cli watchlist import` or add it to [watchlists] in the config",
+            name
+        ),
+    }
+}
@@ -0,0 +1,48 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use clap::Parser;
+use data::{Config, LabelConfig};
+use database::SqliteDb;
+
+use crate::timeframe::TimeframeArg;
+
+/// Label the stored bars with forward returns, run-ups, drawdowns and directions
+#[derive(Parser)]
+pub struct LabelsArgs {
+    #[arg(long, value_enum)]
+    pub timeframe: TimeframeArg,
+    /// Bars to look ahead, defaults to labels.horizons from the config
+    #[arg(long, value_delimiter = ',')]
+    pub horizons: Vec<usize>,
+    /// Defaults to labels.dead_zone from the config
+    #[arg(long)]
+    pub dead_zone: Option<f64>,
+    /// First day to relabel (YYYY-MM-DD), defaults to where the labels stop
+    #[arg(long)]
+    pub from: Option<NaiveDate>,
+    /// Defaults to database.uri from the config
+    #[arg(long)]
+    pub db: Option<String>,
+}
+
+pub async fn run(args: &LabelsArgs, config: &Config) -> Result<()> {
+    let db = SqliteDb::connect(&config.database_uri(args.db.as_deref())?).await?;
+    let mut labels = LabelConfig {
+        horizons: args.horizons.clone(),
+        dead_zone: args.dead_zone.unwrap_or(config.labels.dead_zone),
+    };
+    if labels.horizons.is_empty() {
+        labels.horizons.clone_from(&config.labels.horizons);
+    }
+    let config = Config {
+        labels,
+        ..config.clone()
+    };
+    config.validate()?;
+
+    let rows =
+        data::update_forward_return_labels(&db, args.timeframe.into(), &config.labels, args.from)
+            .await?;
+    println!("Stored {} forward-return labels", rows);
+    Ok(())
+}
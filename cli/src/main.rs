@@ -1,11 +1,39 @@
+mod check;
+mod config;
+mod corporate_actions;
+mod cross_sectional;
 mod database;
+mod export;
+mod import;
+mod ingest;
+mod labels;
+mod resample;
+mod symbols;
+mod timeframe;
+mod watchlist;
+use std::path::PathBuf;
+
 use anyhow::Result;
+use check::CheckArgs;
 use clap::{Parser, Subcommand};
+use config::ConfigArgs;
+use corporate_actions::CorporateActionsArgs;
+use cross_sectional::CrossSectionalArgs;
 use database::DatabaseArgs;
+use export::ExportArgs;
+use import::ImportArgs;
+use ingest::IngestArgs;
+use labels::LabelsArgs;
+use resample::ResampleArgs;
+use symbols::SymbolsArgs;
+use watchlist::WatchlistArgs;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
+    /// Config file, defaults to market_data.toml in the working directory
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
     #[command(subcommand)]
     command: Commands,
 }
@@ -13,13 +41,46 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     Database(DatabaseArgs),
+    Ingest(IngestArgs),
+    Export(ExportArgs),
+    Import(ImportArgs),
+    CorporateActions(CorporateActionsArgs),
+    Check(CheckArgs),
+    Resample(ResampleArgs),
+    Config(ConfigArgs),
+    Watchlist(WatchlistArgs),
+    Symbols(SymbolsArgs),
+    CrossSectional(CrossSectionalArgs),
+    Labels(LabelsArgs),
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
+fn main() -> Result<()> {
     let cli = Cli::parse();
+    dotenvy::dotenv().ok();
+    let config = data::Config::load(cli.config.as_deref())?;
+    // Still single-threaded, the runtime's worker threads start below
+    config.export_credentials();
+
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(run(&cli, &config))
+}
+
+async fn run(cli: &Cli, config: &data::Config) -> Result<()> {
     match &cli.command {
-        Commands::Database(args) => database::run(args).await?,
+        Commands::Database(args) => database::run(args, config).await?,
+        Commands::Ingest(args) => ingest::run(args, config).await?,
+        Commands::Export(args) => export::run(args, config).await?,
+        Commands::Import(args) => import::run(args, config).await?,
+        Commands::CorporateActions(args) => corporate_actions::run(args, config).await?,
+        Commands::Check(args) => check::run(args, config).await?,
+        Commands::Resample(args) => resample::run(args, config).await?,
+        Commands::Config(args) => config::run(args, config).await?,
+        Commands::Watchlist(args) => watchlist::run(args, config).await?,
+        Commands::Symbols(args) => symbols::run(args, config).await?,
+        Commands::CrossSectional(args) => cross_sectional::run(args, config).await?,
+        Commands::Labels(args) => labels::run(args, config).await?,
     }
 
     Ok(())
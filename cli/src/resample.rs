@@ -0,0 +1,64 @@
+use anyhow::Result;
+use clap::Parser;
+use data::{sources::ResampledBarSource, Config, IngestOptions};
+use database::{SqliteDb, Timeframe};
+
+use crate::ingest::AdjustmentArg;
+use crate::timeframe::TimeframeArg;
+
+/// Build hourly, daily, weekly or monthly bars from the stored fifteen-minute bars
+#[derive(Parser)]
+pub struct ResampleArgs {
+    /// Timeframe to build
+    #[arg(long, value_enum)]
+    pub timeframe: TimeframeArg,
+    /// Defaults to every symbol with fifteen-minute bars
+    #[arg(long, value_delimiter = ',')]
+    pub symbols: Vec<String>,
+    /// Only store bars newer than the latest stored bar of each symbol
+    #[arg(long)]
+    pub catch_up: bool,
+    /// Corporate-action adjustment of the stored prices
+    #[arg(long, value_enum, default_value = "raw")]
+    pub adjustment: AdjustmentArg,
+    /// Print the comparison with already fetched bars without storing anything
+    #[arg(long)]
+    pub report_only: bool,
+    /// Defaults to database.uri from the config
+    #[arg(long)]
+    pub db: Option<String>,
+}
+
+pub async fn run(args: &ResampleArgs, config: &Config) -> Result<()> {
+    let db = SqliteDb::connect(&config.database_uri(args.db.as_deref())?).await?;
+    let timeframe: Timeframe = args.timeframe.into();
+    let symbols = if args.symbols.is_empty() {
+        db.list_symbols(Timeframe::FifteenMin).await?
+    } else {
+        args.symbols.iter().map(|s| s.to_uppercase()).collect()
+    };
+    let symbols: Vec<&str> = symbols.iter().map(String::as_str).collect();
+
+    let source = ResampledBarSource::load(&db, timeframe, &symbols).await?;
+    println!("Resampled vs. fetched bars in {}", timeframe.table());
+    for report in source.consistency_report(&db).await? {
+        println!("{}", report);
+    }
+    if args.report_only {
+        return Ok(());
+    }
+
+    let Some(start) = source.first_day() else {
+        println!("No fifteen-minute bars to resample");
+        return Ok(());
+    };
+    let options = IngestOptions {
+        start,
+        end: None,
+        catch_up: args.catch_up,
+        adjustment: args.adjustment.into(),
+        store_from: None,
+        feature_spec: config.feature_spec(timeframe)?,
+    };
+    data::insert_stock_bars(&db, &source, timeframe, symbols, &options).await
+}
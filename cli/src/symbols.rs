@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
+use data::Config;
+use database::{SqliteDb, Symbol};
+
+#[derive(Subcommand)]
+pub enum SymbolsCommands {
+    /// Load ticker reference data from a CSV or JSON file
+    Load {
+        path: PathBuf,
+        /// Defaults to database.uri from the config
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// List the tickers, optionally of one sector
+    List {
+        #[arg(long)]
+        sector: Option<String>,
+        /// Defaults to database.uri from the config
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Print the reference data of a ticker
+    Show {
+        ticker: String,
+        /// Defaults to database.uri from the config
+        #[arg(long)]
+        db: Option<String>,
+    },
+}
+
+#[derive(Parser)]
+pub struct SymbolsArgs {
+    #[command(subcommand)]
+    pub subcommand: SymbolsCommands,
+}
+
+pub async fn run(args: &SymbolsArgs, config: &Config) -> Result<()> {
+    match &args.subcommand {
+        SymbolsCommands::Load { path, db } => {
+            let symbols = data::load_symbols(path)?;
+            let db = SqliteDb::connect(&config.database_uri(db.as_deref())?).await?;
+            let rows = db.upsert_symbols(&symbols).await?;
+            println!("Loaded {} symbols ({} rows written)", symbols.len(), rows);
+            Ok(())
+        }
+        SymbolsCommands::List { sector, db } => {
+            let db = SqliteDb::connect(&config.database_uri(db.as_deref())?).await?;
+            for symbol in db.symbols(sector.as_deref()).await? {
+                print_symbol(&symbol);
+            }
+            Ok(())
+        }
+        SymbolsCommands::Show { ticker, db } => {
+            let db = SqliteDb::connect(&config.database_uri(db.as_deref())?).await?;
+            let symbol = db
+                .symbol(&ticker.to_uppercase())
+                .await?
+                .ok_or_else(|| anyhow!("Unknown symbol: {}", ticker))?;
+            print_symbol(&symbol);
+            Ok(())
+        }
+    }
+}
+
+fn print_symbol(symbol: &Symbol) {
+    let date = |date: Option<chrono::NaiveDate>| date.map_or("-".to_string(), |d| d.to_string());
+    println!(
+        "{:<6} {:<5} {:<24} {:<5} {:<8} {} to {}{}  {}",
+        symbol.ticker,
+        symbol.asset_class.as_str(),
+        symbol.sector.as_deref().unwrap_or("-"),
+        symbol.benchmark_etf.as_deref().unwrap_or("-"),
+        symbol.exchange.as_deref().unwrap_or("-"),
+        date(symbol.first_bar_date),
+        date(symbol.last_bar_date),
+        if symbol.active { "" } else { " (delisted)" },
+        symbol.name.as_deref().unwrap_or("")
+    );
+}
@@ -0,0 +1,38 @@
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use database::Timeframe;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum TimeframeArg {
+    Monthly,
+    Weekly,
+    Daily,
+    Hourly,
+    FifteenMin,
+}
+
+impl From<TimeframeArg> for Timeframe {
+    fn from(timeframe: TimeframeArg) -> Self {
+        match timeframe {
+            TimeframeArg::Monthly => Timeframe::Monthly,
+            TimeframeArg::Weekly => Timeframe::Weekly,
+            TimeframeArg::Daily => Timeframe::Daily,
+            TimeframeArg::Hourly => Timeframe::Hourly,
+            TimeframeArg::FifteenMin => Timeframe::FifteenMin,
+        }
+    }
+}
+
+/// Timeframe given either as `--timeframe` or as a bar table name with `--table`.
+pub fn resolve_timeframe(
+    timeframe: Option<TimeframeArg>,
+    table: Option<&str>,
+) -> Result<Timeframe> {
+    match (table, timeframe) {
+        (Some(table), _) => {
+            Timeframe::from_table(table).ok_or_else(|| anyhow!("Unknown bar table: {}", table))
+        }
+        (None, Some(timeframe)) => Ok(timeframe.into()),
+        (None, None) => Err(anyhow!("Either --timeframe or --table is required")),
+    }
+}
@@ -0,0 +1,133 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use data::Config;
+use database::SqliteDb;
+
+#[derive(Subcommand)]
+pub enum WatchlistCommands {
+    /// Create a watchlist, optionally with its first symbols
+    Create {
+        name: String,
+        #[arg(long)]
+        description: Option<String>,
+        symbols: Vec<String>,
+        /// Defaults to database.uri from the config
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Add symbols to a watchlist
+    Add {
+        name: String,
+        #[arg(required = true)]
+        symbols: Vec<String>,
+        /// Defaults to database.uri from the config
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Remove symbols from a watchlist
+    Remove {
+        name: String,
+        #[arg(required = true)]
+        symbols: Vec<String>,
+        /// Defaults to database.uri from the config
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// List the watchlists, or the symbols of one
+    List {
+        name: Option<String>,
+        /// Defaults to database.uri from the config
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Import members from a CSV with a watchlist,symbol header, or from the
+    /// [watchlists] of the config when no file is given. Missing watchlists are
+    /// created.
+    Import {
+        path: Option<PathBuf>,
+        /// Defaults to database.uri from the config
+        #[arg(long)]
+        db: Option<String>,
+    },
+}
+
+#[derive(Parser)]
+pub struct WatchlistArgs {
+    #[command(subcommand)]
+    pub subcommand: WatchlistCommands,
+}
+
+pub async fn run(args: &WatchlistArgs, config: &Config) -> Result<()> {
+    match &args.subcommand {
+        WatchlistCommands::Create {
+            name,
+            description,
+            symbols,
+            db,
+        } => {
+            let db = SqliteDb::connect(&config.database_uri(db.as_deref())?).await?;
+            db.create_watchlist(name, description.as_deref()).await?;
+            let added = db.add_watchlist_symbols(name, symbols).await?;
+            println!("Created watchlist {} with {} symbols", name, added);
+            Ok(())
+        }
+        WatchlistCommands::Add { name, symbols, db } => {
+            let db = SqliteDb::connect(&config.database_uri(db.as_deref())?).await?;
+            let added = db.add_watchlist_symbols(name, symbols).await?;
+            println!("Added {} symbols to {}", added, name);
+            Ok(())
+        }
+        WatchlistCommands::Remove { name, symbols, db } => {
+            let db = SqliteDb::connect(&config.database_uri(db.as_deref())?).await?;
+            let removed = db.remove_watchlist_symbols(name, symbols).await?;
+            println!("Removed {} symbols from {}", removed, name);
+            Ok(())
+        }
+        WatchlistCommands::List { name, db } => {
+            let db = SqliteDb::connect(&config.database_uri(db.as_deref())?).await?;
+            match name {
+                Some(name) => println!("{}", db.watchlist_symbols(name).await?.join(",")),
+                None => {
+                    for watchlist in db.watchlists().await? {
+                        println!(
+                            "{:<24} {:>4} symbols  {}",
+                            watchlist.name,
+                            watchlist.members,
+                            watchlist.description.unwrap_or_default()
+                        );
+                    }
+                }
+            }
+            Ok(())
+        }
+        WatchlistCommands::Import { path, db } => {
+            let watchlists = match path {
+                Some(path) => data::watchlist::load_watchlist_members(path)?,
+                None => config.watchlists.clone(),
+            };
+            let db = SqliteDb::connect(&config.database_uri(db.as_deref())?).await?;
+            import(&db, &watchlists).await
+        }
+    }
+}
+
+async fn import(db: &SqliteDb, watchlists: &BTreeMap<String, Vec<String>>) -> Result<()> {
+    let existing: Vec<String> = db
+        .watchlists()
+        .await?
+        .into_iter()
+        .map(|watchlist| watchlist.name)
+        .collect();
+
+    for (name, symbols) in watchlists {
+        if !existing.contains(name) {
+            db.create_watchlist(name, None).await?;
+            println!("Created watchlist {}", name);
+        }
+        let added = db.add_watchlist_symbols(name, symbols).await?;
+        println!("Added {} symbols to {}", added, name);
+    }
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use std::{fs::File, path::Path};
+
+use alpaca_api_client::market_data::stocks::StockBar;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate};
+use chrono_tz::America::New_York;
+use database::{AdjustmentMode, CorporateAction, CorporateActionType};
+
+/// Reads corporate actions from a CSV file with a
+/// `stock_symbol,action_type,ex_date,split_ratio,cash_amount` header, or from a
+/// JSON array of objects with the same fields.
+pub fn load_corporate_actions(path: &Path) -> Result<Vec<CorporateAction>> {
+    let actions: Vec<CorporateAction> = match path.extension().and_then(|e| e.to_str()) {
+        Some("csv") => {
+            let mut reader = csv::Reader::from_path(path)?;
+            reader
+                .deserialize()
+                .enumerate()
+                .map(|(index, action)| {
+                    action.with_context(|| format!("{}:{}", path.display(), index + 2))
+                })
+                .collect::<Result<_>>()?
+        }
+        Some("json") => serde_json::from_reader(File::open(path)?)
+            .with_context(|| format!("Invalid corporate actions in {}", path.display()))?,
+        _ => bail!("Expected a .csv or .json file: {}", path.display()),
+    };
+
+    for action in &actions {
+        let valid = match action.action_type {
+            CorporateActionType::Split => action.split_ratio.is_some_and(|ratio| ratio > 0.0),
+            CorporateActionType::Dividend => action.cash_amount.is_some_and(|cash| cash > 0.0),
+        };
+        if !valid {
+            bail!(
+                "{} {:?} on {} needs a positive split_ratio or cash_amount",
+                action.stock_symbol,
+                action.action_type,
+                action.ex_date
+            );
+        }
+    }
+
+    Ok(actions)
+}
+
+/// Back-adjusts the bars of one symbol, oldest first, so prices before each
+/// action line up with the prices after it. The most recent bars keep their
+/// traded prices.
+///
+/// A split divides earlier prices by its ratio and multiplies earlier volume by
+/// it. In total-return mode a dividend also scales earlier prices by
+/// `1 - cash / close`, using the close of the last bar before the ex-date.
+pub fn adjust_bars(
+    bars: &mut [StockBar],
+    actions: &[CorporateAction],
+    mode: AdjustmentMode,
+) -> Result<()> {
+    if mode == AdjustmentMode::Raw || actions.is_empty() {
+        return Ok(());
+    }
+
+    let dates = bars
+        .iter()
+        .map(|bar| market_date(&bar.t))
+        .collect::<Result<Vec<_>>>()?;
+
+    // (ex-date, price factor, volume factor) of every action that applies
+    let mut factors = Vec::new();
+    for action in actions {
+        match (action.action_type, action.split_ratio, action.cash_amount) {
+            (CorporateActionType::Split, Some(ratio), _) if ratio > 0.0 => {
+                factors.push((action.ex_date, 1.0 / ratio, ratio));
+            }
+            (CorporateActionType::Dividend, _, Some(cash))
+                if mode == AdjustmentMode::TotalReturn =>
+            {
+                let Some(previous) = dates.iter().rposition(|date| *date < action.ex_date) else {
+                    continue;
+                };
+                let close = bars[previous].c as f64;
+                if close > cash {
+                    factors.push((action.ex_date, 1.0 - cash / close, 1.0));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (bar, date) in bars.iter_mut().zip(&dates) {
+        let (price, volume) = factors.iter().filter(|(ex_date, ..)| date < ex_date).fold(
+            (1.0, 1.0),
+            |(price, volume), (_, price_factor, volume_factor)| {
+                (price * price_factor, volume * volume_factor)
+            },
+        );
+        if price == 1.0 && volume == 1.0 {
+            continue;
+        }
+
+        let scale = |value: f32| (value as f64 * price) as f32;
+        bar.o = scale(bar.o);
+        bar.h = scale(bar.h);
+        bar.l = scale(bar.l);
+        bar.c = scale(bar.c);
+        bar.vw = scale(bar.vw);
+        bar.v = (bar.v as f64 * volume) as f32;
+    }
+
+    Ok(())
+}
+
+/// Trading day of a bar timestamp, in exchange time.
+fn market_date(timestamp: &str) -> Result<NaiveDate> {
+    let datetime = DateTime::parse_from_rfc3339(timestamp)
+        .with_context(|| format!("Invalid bar timestamp: {}", timestamp))?;
+    Ok(datetime.with_timezone(&New_York).date_naive())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(t: &str, close: f32) -> StockBar {
+        serde_json::from_value(serde_json::json!({
+            "t": t, "o": close, "h": close, "l": close, "c": close, "v": 100.0, "n": 1, "vw": close,
+        }))
+        .unwrap()
+    }
+
+    fn action(
+        action_type: CorporateActionType,
+        ex_date: &str,
+        split_ratio: Option<f64>,
+        cash_amount: Option<f64>,
+    ) -> CorporateAction {
+        CorporateAction {
+            stock_symbol: "NVDA".to_string(),
+            action_type,
+            ex_date: ex_date.parse().unwrap(),
+            split_ratio,
+            cash_amount,
+        }
+    }
+
+    #[test]
+    fn test_adjust_bars_for_split_and_dividend() {
+        let actions = [
+            action(CorporateActionType::Split, "2024-06-10", Some(10.0), None),
+            action(CorporateActionType::Dividend, "2024-06-11", None, Some(0.1)),
+        ];
+        let raw = || {
+            vec![
+                bar("2024-06-07T04:00:00Z", 1200.0),
+                bar("2024-06-10T04:00:00Z", 121.0),
+                bar("2024-06-11T04:00:00Z", 120.0),
+            ]
+        };
+
+        let mut split = raw();
+        adjust_bars(&mut split, &actions, AdjustmentMode::Split).unwrap();
+        let closes: Vec<f32> = split.iter().map(|bar| bar.c).collect();
+        assert_eq!(closes, vec![120.0, 121.0, 120.0]);
+        assert_eq!(split[0].v, 1000.0);
+
+        let mut total_return = raw();
+        adjust_bars(&mut total_return, &actions, AdjustmentMode::TotalReturn).unwrap();
+        let dividend_factor = 1.0 - 0.1 / 121.0;
+        assert!((total_return[1].c as f64 - 121.0 * dividend_factor).abs() < 1e-3);
+        assert!((total_return[0].c as f64 - 120.0 * dividend_factor).abs() < 1e-3);
+        assert_eq!(total_return[2].c, 120.0);
+    }
+}
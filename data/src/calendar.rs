@@ -0,0 +1,217 @@
+//! NYSE trading calendar: full-day holidays, early closes and session hours.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::America::New_York;
+
+/// Unscheduled full-day closures.
+const SPECIAL_CLOSURES: [(i32, u32, u32); 2] = [
+    // National day of mourning for George H. W. Bush
+    (2018, 12, 5),
+    // National day of mourning for Jimmy Carter
+    (2025, 1, 9),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Session {
+    /// 9:30 to 16:00, or 13:00 on early-close days.
+    Regular,
+    /// 4:00 to 20:00, or 17:00 on early-close days.
+    Extended,
+}
+
+/// Full-day market holidays of a year, in date order.
+pub fn holidays(year: i32) -> Vec<NaiveDate> {
+    let mut holidays = vec![
+        // New Year's Day falling on a Saturday is not observed on the Friday before
+        Some(observed(date(year, 1, 1))).filter(|day| day.year() == year),
+        Some(nth_weekday(year, 1, Weekday::Mon, 3)),
+        Some(nth_weekday(year, 2, Weekday::Mon, 3)),
+        Some(easter_sunday(year) - Duration::days(2)),
+        Some(last_weekday(year, 5, Weekday::Mon)),
+        (year >= 2022).then(|| observed(date(year, 6, 19))),
+        Some(observed(date(year, 7, 4))),
+        Some(nth_weekday(year, 9, Weekday::Mon, 1)),
+        Some(nth_weekday(year, 11, Weekday::Thu, 4)),
+        Some(observed(date(year, 12, 25))),
+    ];
+    holidays.extend(
+        SPECIAL_CLOSURES
+            .iter()
+            .filter(|(closure_year, ..)| *closure_year == year)
+            .map(|(year, month, day)| Some(date(*year, *month, *day))),
+    );
+
+    let mut holidays: Vec<NaiveDate> = holidays.into_iter().flatten().collect();
+    holidays.sort();
+    holidays
+}
+
+pub fn is_holiday(day: NaiveDate) -> bool {
+    holidays(day.year()).contains(&day)
+}
+
+pub fn is_trading_day(day: NaiveDate) -> bool {
+    !matches!(day.weekday(), Weekday::Sat | Weekday::Sun) && !is_holiday(day)
+}
+
+/// Early-close days: the day before Independence Day, the day after
+/// Thanksgiving and Christmas Eve, when they are trading days.
+pub fn is_half_day(day: NaiveDate) -> bool {
+    let year = day.year();
+    let candidates = [
+        date(year, 7, 3),
+        nth_weekday(year, 11, Weekday::Thu, 4) + Duration::days(1),
+        date(year, 12, 24),
+    ];
+    candidates.contains(&day) && is_trading_day(day)
+}
+
+/// Open and close of a session in UTC, `None` when the market is closed.
+pub fn session_hours(day: NaiveDate, session: Session) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    if !is_trading_day(day) {
+        return None;
+    }
+
+    let half_day = is_half_day(day);
+    let (open, close) = match session {
+        Session::Regular => ((9, 30), if half_day { (13, 0) } else { (16, 0) }),
+        Session::Extended => ((4, 0), if half_day { (17, 0) } else { (20, 0) }),
+    };
+    Some((exchange_time(day, open)?, exchange_time(day, close)?))
+}
+
+/// Trading days from `from` to `to`, inclusive.
+pub fn trading_days(from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+    from.iter_days()
+        .take_while(|day| *day <= to)
+        .filter(|day| is_trading_day(*day))
+        .collect()
+}
+
+pub fn previous_trading_day(day: NaiveDate) -> NaiveDate {
+    let mut previous = day - Duration::days(1);
+    while !is_trading_day(previous) {
+        previous -= Duration::days(1);
+    }
+    previous
+}
+
+pub fn next_trading_day(day: NaiveDate) -> NaiveDate {
+    let mut next = day + Duration::days(1);
+    while !is_trading_day(next) {
+        next += Duration::days(1);
+    }
+    next
+}
+
+/// Exchange date of a UTC instant.
+pub fn exchange_date(datetime: DateTime<Utc>) -> NaiveDate {
+    datetime.with_timezone(&New_York).date_naive()
+}
+
+/// Start of an exchange day in UTC, which is how Alpaca stamps daily and
+/// weekly bars.
+pub fn exchange_midnight(day: NaiveDate) -> DateTime<Utc> {
+    exchange_time(day, (0, 0)).expect("exchange midnight is never skipped or repeated")
+}
+
+/// Monday of the week of `day`.
+pub fn week_start(day: NaiveDate) -> NaiveDate {
+    day - Duration::days(day.weekday().num_days_from_monday() as i64)
+}
+
+/// First day of the month of `day`.
+pub fn month_start(day: NaiveDate) -> NaiveDate {
+    day.with_day(1).expect("every month has a first day")
+}
+
+fn exchange_time(day: NaiveDate, (hour, minute): (u32, u32)) -> Option<DateTime<Utc>> {
+    let time = NaiveTime::from_hms_opt(hour, minute, 0)?;
+    New_York
+        .from_local_datetime(&day.and_time(time))
+        .single()
+        .map(|datetime| datetime.with_timezone(&Utc))
+}
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid calendar date")
+}
+
+/// Saturday holidays move to Friday and Sunday holidays to Monday.
+fn observed(day: NaiveDate) -> NaiveDate {
+    match day.weekday() {
+        Weekday::Sat => day - Duration::days(1),
+        Weekday::Sun => day + Duration::days(1),
+        _ => day,
+    }
+}
+
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: u8) -> NaiveDate {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, n).expect("valid weekday of month")
+}
+
+fn last_weekday(year: i32, month: u32, weekday: Weekday) -> NaiveDate {
+    let mut day = date(year, month + 1, 1) - Duration::days(1);
+    while day.weekday() != weekday {
+        day -= Duration::days(1);
+    }
+    day
+}
+
+/// Anonymous Gregorian algorithm.
+fn easter_sunday(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    date(year, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nyse_calendar() {
+        let expected_2024: Vec<NaiveDate> = [
+            (1, 1),
+            (1, 15),
+            (2, 19),
+            (3, 29),
+            (5, 27),
+            (6, 19),
+            (7, 4),
+            (9, 2),
+            (11, 28),
+            (12, 25),
+        ]
+        .iter()
+        .map(|(month, day)| date(2024, *month, *day))
+        .collect();
+        assert_eq!(holidays(2024), expected_2024);
+
+        // New Year's Day 2022 fell on a Saturday and was not observed
+        assert!(is_trading_day(date(2021, 12, 31)));
+        // Independence Day 2021 fell on a Sunday
+        assert!(is_holiday(date(2021, 7, 5)));
+        assert!(!is_trading_day(date(2025, 1, 9)));
+
+        assert!(is_half_day(date(2024, 11, 29)));
+        assert!(!is_half_day(date(2021, 12, 24)));
+        let (open, close) = session_hours(date(2024, 7, 3), Session::Regular).unwrap();
+        assert_eq!(open.to_rfc3339(), "2024-07-03T13:30:00+00:00");
+        assert_eq!(close.to_rfc3339(), "2024-07-03T17:00:00+00:00");
+
+        assert_eq!(previous_trading_day(date(2024, 7, 5)), date(2024, 7, 3));
+    }
+}
@@ -0,0 +1,374 @@
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::{anyhow, bail, Context, Result};
+use database::{FeatureSpec, PriceField, Timeframe, CORE_COLUMNS};
+use serde::{Deserialize, Serialize};
+
+/// Config file read when no path is given, relative to the working directory.
+pub const DEFAULT_CONFIG_FILE: &str = "market_data.toml";
+
+/// Environment variables overriding the config file.
+pub const CONFIG_FILE_ENV: &str = "MARKET_DATA_CONFIG";
+pub const DATABASE_URL_ENV: &str = "DATABASE_URL";
+pub const ALPACA_KEY_ID_ENV: &str = "APCA_API_KEY_ID";
+pub const ALPACA_SECRET_KEY_ENV: &str = "APCA_API_SECRET_KEY";
+pub const START_ENV: &str = "MARKET_DATA_START";
+pub const REQUESTS_PER_MINUTE_ENV: &str = "MARKET_DATA_REQUESTS_PER_MINUTE";
+pub const MAX_RETRIES_ENV: &str = "MARKET_DATA_MAX_RETRIES";
+pub const MAX_CONCURRENCY_ENV: &str = "MARKET_DATA_MAX_CONCURRENCY";
+
+/// Workspace settings, layered from lowest to highest precedence:
+/// 1. `market_data.toml` (or the file named by `MARKET_DATA_CONFIG`)
+/// 2. environment variables, including a `.env` file loaded by the cli
+/// 3. cli flags, applied by each command
+///
+/// ```toml
+/// [database]
+/// uri = "sqlite://stocks.db"
+///
+/// [ingest]
+/// start = "2016-01-01"
+///
+/// [rate_limit]
+/// requests_per_minute = 200
+/// max_concurrency = 4
+///
+/// [watchlists]
+/// banks = ["JPM", "BAC", "WFC"]
+///
+/// [labels]
+/// horizons = [1, 5, 10, 20]
+/// dead_zone = 0.005
+///
+/// [[features.daily_stock_bars]]
+/// column = "two_hundred_day_sma"
+/// indicator = "sma"
+/// period = 200
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub database: DatabaseConfig,
+    pub alpaca: AlpacaConfig,
+    pub ingest: IngestConfig,
+    pub rate_limit: RateLimitConfig,
+    /// Named symbol lists, copied into the database by `cli watchlist import`.
+    pub watchlists: BTreeMap<String, Vec<String>>,
+    /// Indicator columns added to the default feature spec, per bar table.
+    pub features: BTreeMap<String, Vec<FeatureConfig>>,
+    pub labels: LabelConfig,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub uri: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlpacaConfig {
+    pub key_id: Option<String>,
+    pub secret_key: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IngestConfig {
+    /// First day fetched when a command is not given `--start`.
+    pub start: String,
+}
+
+impl Default for IngestConfig {
+    fn default() -> Self {
+        Self {
+            start: "2016-01-01".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    /// Alpaca's free plan allows 200.
+    pub requests_per_minute: u32,
+    /// Retries of a failed request before giving up.
+    pub max_retries: u32,
+    /// Requests in flight at once.
+    pub max_concurrency: usize,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_minute: 200,
+            max_retries: 5,
+            max_concurrency: 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LabelConfig {
+    /// Bars ahead of each bar that `cli labels` looks at.
+    pub horizons: Vec<usize>,
+    /// Forward returns within this distance of zero are labelled flat.
+    pub dead_zone: f64,
+}
+
+impl Default for LabelConfig {
+    fn default() -> Self {
+        Self {
+            horizons: vec![1, 5, 10, 20],
+            dead_zone: 0.005,
+        }
+    }
+}
+
+/// An extra indicator column.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeatureConfig {
+    pub column: String,
+    pub indicator: IndicatorKind,
+    #[serde(default)]
+    pub source: SourceField,
+    pub period: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndicatorKind {
+    Sma,
+    Ema,
+    Rsi,
+    High,
+    Low,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceField {
+    Open,
+    High,
+    Low,
+    #[default]
+    Close,
+    Volume,
+}
+
+impl From<SourceField> for PriceField {
+    fn from(source: SourceField) -> Self {
+        match source {
+            SourceField::Open => PriceField::Open,
+            SourceField::High => PriceField::High,
+            SourceField::Low => PriceField::Low,
+            SourceField::Close => PriceField::Close,
+            SourceField::Volume => PriceField::Volume,
+        }
+    }
+}
+
+impl Config {
+    /// Reads `path`, or the default config file when it exists, then applies
+    /// the environment.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path = path
+            .map(Path::to_path_buf)
+            .or_else(|| std::env::var_os(CONFIG_FILE_ENV).map(Into::into));
+        let mut config = match path {
+            Some(path) => Self::from_file(&path)?,
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+                Self::from_file(Path::new(DEFAULT_CONFIG_FILE))?
+            }
+            None => Self::default(),
+        };
+        config.apply_env(|name| std::env::var(name).ok())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Cannot read config file {}", path.display()))?;
+        toml::from_str(&contents).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    /// Overrides settings with the variables `var` returns a value for.
+    pub fn apply_env(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<()> {
+        if let Some(uri) = var(DATABASE_URL_ENV) {
+            self.database.uri = Some(uri);
+        }
+        if let Some(key_id) = var(ALPACA_KEY_ID_ENV) {
+            self.alpaca.key_id = Some(key_id);
+        }
+        if let Some(secret_key) = var(ALPACA_SECRET_KEY_ENV) {
+            self.alpaca.secret_key = Some(secret_key);
+        }
+        if let Some(start) = var(START_ENV) {
+            self.ingest.start = start;
+        }
+        if let Some(requests) = var(REQUESTS_PER_MINUTE_ENV) {
+            self.rate_limit.requests_per_minute = requests
+                .parse()
+                .with_context(|| format!("Invalid {}: {}", REQUESTS_PER_MINUTE_ENV, requests))?;
+        }
+        if let Some(retries) = var(MAX_RETRIES_ENV) {
+            self.rate_limit.max_retries = retries
+                .parse()
+                .with_context(|| format!("Invalid {}: {}", MAX_RETRIES_ENV, retries))?;
+        }
+        if let Some(concurrency) = var(MAX_CONCURRENCY_ENV) {
+            self.rate_limit.max_concurrency = concurrency
+                .parse()
+                .with_context(|| format!("Invalid {}: {}", MAX_CONCURRENCY_ENV, concurrency))?;
+        }
+        Ok(())
+    }
+
+    /// Checks the settings that cannot be checked by deserializing alone.
+    pub fn validate(&self) -> Result<()> {
+        if self.rate_limit.requests_per_minute == 0 {
+            bail!("rate_limit.requests_per_minute must be positive");
+        }
+        if self.rate_limit.max_concurrency == 0 {
+            bail!("rate_limit.max_concurrency must be positive");
+        }
+        if self.labels.horizons.is_empty() || self.labels.horizons.contains(&0) {
+            bail!("labels.horizons must be positive and not empty");
+        }
+        if !(self.labels.dead_zone >= 0.0 && self.labels.dead_zone.is_finite()) {
+            bail!("labels.dead_zone must be zero or positive");
+        }
+        for table in self.features.keys() {
+            let timeframe = Timeframe::from_table(table)
+                .ok_or_else(|| anyhow!("Unknown bar table in [features]: {}", table))?;
+            self.feature_spec(timeframe)?;
+        }
+        Ok(())
+    }
+
+    /// Database URI from the `--db`/`--uri` flag, falling back to the config.
+    pub fn database_uri(&self, flag: Option<&str>) -> Result<String> {
+        flag.map(str::to_string)
+            .or_else(|| self.database.uri.clone())
+            .ok_or_else(|| {
+                anyhow!(
+                    "No database URI: pass one, set {} or add database.uri to {}",
+                    DATABASE_URL_ENV,
+                    DEFAULT_CONFIG_FILE
+                )
+            })
+    }
+
+    /// Symbols of a configured watchlist.
+    pub fn watchlist(&self, name: &str) -> Result<&[String]> {
+        self.watchlists
+            .get(name)
+            .map(Vec::as_slice)
+            .ok_or_else(|| anyhow!("Unknown watchlist: {}", name))
+    }
+
+    /// The timeframe's default feature spec followed by the configured extra
+    /// columns.
+    pub fn feature_spec(&self, timeframe: Timeframe) -> Result<FeatureSpec> {
+        let mut spec = timeframe.feature_spec();
+        for feature in self.features.get(timeframe.table()).into_iter().flatten() {
+            let column = feature.column.as_str();
+            let valid = !column.is_empty()
+                && column
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+            if !valid || CORE_COLUMNS.contains(&column) || spec.columns().contains(&column) {
+                bail!(
+                    "Invalid or duplicate feature column for {}: {}",
+                    timeframe.table(),
+                    column
+                );
+            }
+            if feature.period == 0 {
+                bail!("Feature {} needs a positive period", column);
+            }
+
+            let source = feature.source.into();
+            spec = match feature.indicator {
+                IndicatorKind::Sma => spec.sma(column, source, feature.period),
+                IndicatorKind::Ema => spec.ema(column, source, feature.period),
+                IndicatorKind::Rsi => spec.rsi(column, source, feature.period),
+                IndicatorKind::High => spec.high(column, source, feature.period),
+                IndicatorKind::Low => spec.low(column, source, feature.period),
+            };
+        }
+        Ok(spec)
+    }
+
+    /// Exports the configured Alpaca keys to the environment, where the Alpaca
+    /// client reads them. Setting environment variables is only sound while
+    /// no other thread runs, so call this before starting the async runtime.
+    pub fn export_credentials(&self) {
+        if let Some(key_id) = &self.alpaca.key_id {
+            std::env::set_var(ALPACA_KEY_ID_ENV, key_id);
+        }
+        if let Some(secret_key) = &self.alpaca.secret_key {
+            std::env::set_var(ALPACA_SECRET_KEY_ENV, secret_key);
+        }
+    }
+
+    /// The effective settings as TOML, with the secret key masked.
+    pub fn to_toml(&self) -> Result<String> {
+        let mut shown = self.clone();
+        if shown.alpaca.secret_key.is_some() {
+            shown.alpaca.secret_key = Some("********".to_string());
+        }
+        Ok(toml::to_string_pretty(&shown)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_env_overrides_file_and_features_extend_the_spec() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [database]
+            uri = "sqlite://file.db"
+
+            [watchlists]
+            banks = ["JPM", "WFC"]
+
+            [[features.daily_stock_bars]]
+            column = "two_hundred_day_sma"
+            indicator = "sma"
+            period = 200
+            "#,
+        )
+        .unwrap();
+        let env = HashMap::from([
+            (DATABASE_URL_ENV, "sqlite://env.db"),
+            (REQUESTS_PER_MINUTE_ENV, "100"),
+        ]);
+        config
+            .apply_env(|name| env.get(name).map(|value| value.to_string()))
+            .unwrap();
+        config.validate().unwrap();
+
+        assert_eq!(config.database_uri(None).unwrap(), "sqlite://env.db");
+        assert_eq!(
+            config.database_uri(Some("sqlite://flag.db")).unwrap(),
+            "sqlite://flag.db"
+        );
+        assert_eq!(config.rate_limit.requests_per_minute, 100);
+        assert_eq!(config.ingest.start, "2016-01-01");
+        assert_eq!(config.watchlist("banks").unwrap(), ["JPM", "WFC"]);
+
+        let spec = config.feature_spec(Timeframe::Daily).unwrap();
+        assert_eq!(spec.len(), Timeframe::Daily.feature_spec().len() + 1);
+        assert_eq!(spec.warm_up_bars(), 200);
+        assert!(!config.to_toml().unwrap().is_empty());
+    }
+}
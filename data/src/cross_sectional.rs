@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, NaiveDate};
+use database::{
+    CrossSectionalFeatures, CrossSectionalScore, FeatureSpec, SqliteDb, StockBarsQuery, Timeframe,
+};
+use futures_util::TryStreamExt;
+
+use crate::calendar;
+use crate::ingest::warm_up;
+use crate::relative::{growth, percentile_rank};
+use crate::watchlist::get_all_unique_stock_symbols;
+
+/// Daily feature columns the cross-sectional features are computed from.
+pub const CROSS_SECTIONAL_RSI_COLUMN: &str = "fourteen_day_rsi";
+pub const CROSS_SECTIONAL_SMA_COLUMN: &str = "fifty_day_sma";
+
+/// Bars spanned by the twenty-day return.
+const RETURN_BARS: usize = 20;
+
+/// RSI, twenty-day return, volume and distance from the SMA of one bar.
+type Metrics = [Option<f64>; 4];
+
+/// Ranks the daily bars of every symbol on a stored watchlist against each
+/// other, day by day, and stores the features of days from `from` on. Without
+/// `from` it continues from the latest stored day. `spec` is the daily table's
+/// feature spec, which needs the RSI and SMA columns. Returns how many rows
+/// were written.
+pub async fn update_cross_sectional_features(
+    db: &SqliteDb,
+    spec: &FeatureSpec,
+    from: Option<NaiveDate>,
+) -> Result<u64> {
+    for column in [CROSS_SECTIONAL_RSI_COLUMN, CROSS_SECTIONAL_SMA_COLUMN] {
+        if !spec.columns().contains(&column) {
+            bail!("The daily feature spec has no {} column", column);
+        }
+    }
+    let from = match from {
+        Some(from) => Some(calendar::exchange_midnight(from).timestamp_millis()),
+        None => db.latest_cross_sectional_features_timestamp().await?,
+    };
+
+    let universe = get_all_unique_stock_symbols(db).await?;
+    if universe.is_empty() {
+        bail!("No symbols on any watchlist, add some with `cli watchlist import`");
+    }
+    let universe: Vec<&str> = universe.iter().map(String::as_str).collect();
+    let mut query = StockBarsQuery::new(Timeframe::Daily)
+        .symbols(&universe)
+        .columns(&[
+            "stock_symbol",
+            "event_unix_timestamp",
+            "close_price",
+            "volume",
+            CROSS_SECTIONAL_RSI_COLUMN,
+            CROSS_SECTIONAL_SMA_COLUMN,
+        ]);
+    if let Some(from) = from {
+        // Earlier bars only warm up the twenty-day return
+        let load_from = from - warm_up(Timeframe::Daily, RETURN_BARS + 1).num_milliseconds();
+        if let Some(load_from) = DateTime::from_timestamp_millis(load_from) {
+            query = query.start(load_from);
+        }
+    }
+
+    // Bars come ordered by symbol and then time
+    let mut days: BTreeMap<i64, Vec<(String, Metrics)>> = BTreeMap::new();
+    let mut symbol = String::new();
+    let mut closes: Vec<f64> = Vec::new();
+    let mut bars = query.stream(db)?;
+    while let Some(bar) = bars.try_next().await? {
+        if bar.stock_symbol != symbol {
+            symbol.clone_from(&bar.stock_symbol);
+            closes.clear();
+        }
+        let close = f64::from(bar.close_price);
+        closes.push(close);
+        if bar.event_unix_timestamp < from.unwrap_or(i64::MIN) {
+            continue;
+        }
+
+        let twenty_day_return = closes
+            .len()
+            .checked_sub(RETURN_BARS + 1)
+            .and_then(|start| growth(closes[start], close));
+        let sma_distance = bar
+            .feature(CROSS_SECTIONAL_SMA_COLUMN)
+            .and_then(|sma| growth(f64::from(sma), close));
+        let metrics = [
+            bar.feature(CROSS_SECTIONAL_RSI_COLUMN).map(f64::from),
+            twenty_day_return.map(|growth| growth - 1.0),
+            Some(f64::from(bar.volume)),
+            sma_distance.map(|growth| growth - 1.0),
+        ];
+        days.entry(bar.event_unix_timestamp)
+            .or_default()
+            .push((bar.stock_symbol, metrics));
+    }
+
+    let rows = cross_sectional_features(days);
+    db.upsert_cross_sectional_features(&rows).await
+}
+
+/// Scores every bar against the other bars of its day.
+fn cross_sectional_features(
+    days: BTreeMap<i64, Vec<(String, Metrics)>>,
+) -> Vec<CrossSectionalFeatures> {
+    let mut rows = Vec::new();
+    for (timestamp, bars) in days {
+        let mut scores = vec![[CrossSectionalScore::default(); 4]; bars.len()];
+        for metric in 0..4 {
+            let day = Distribution::new(bars.iter().filter_map(|(_, metrics)| metrics[metric]));
+            for (score, (_, metrics)) in scores.iter_mut().zip(&bars) {
+                if let Some(value) = metrics[metric] {
+                    score[metric] = day.score(value);
+                }
+            }
+        }
+
+        let universe_size = bars.len() as i64;
+        for ((stock_symbol, _), [rsi, twenty_day_return, volume, fifty_day_sma_distance]) in
+            bars.into_iter().zip(scores)
+        {
+            rows.push(CrossSectionalFeatures {
+                stock_symbol,
+                event_unix_timestamp: timestamp,
+                universe_size,
+                rsi,
+                twenty_day_return,
+                volume,
+                fifty_day_sma_distance,
+            });
+        }
+    }
+    rows
+}
+
+/// One metric's values across the universe on a day.
+struct Distribution {
+    sorted: Vec<f64>,
+    mean: f64,
+    std_dev: f64,
+}
+
+impl Distribution {
+    fn new(values: impl Iterator<Item = f64>) -> Self {
+        let mut sorted: Vec<f64> = values.filter(|value| value.is_finite()).collect();
+        sorted.sort_by(f64::total_cmp);
+        let n = sorted.len().max(1) as f64;
+        let mean = sorted.iter().sum::<f64>() / n;
+        let variance = sorted
+            .iter()
+            .map(|value| (value - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        Self {
+            sorted,
+            mean,
+            std_dev: variance.sqrt(),
+        }
+    }
+
+    fn score(&self, value: f64) -> CrossSectionalScore {
+        if !value.is_finite() {
+            return CrossSectionalScore::default();
+        }
+        let above = self.sorted.len() - self.sorted.partition_point(|other| *other <= value);
+        CrossSectionalScore {
+            rank: Some(above as i64 + 1),
+            percentile: percentile_rank(&self.sorted, value).map(|percentile| percentile as f32),
+            // Undefined when every symbol has the same value
+            zscore: (self.std_dev > 1e-12).then(|| ((value - self.mean) / self.std_dev) as f32),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cross_sectional_ranks_percentiles_and_zscores() {
+        let bar = |symbol: &str, rsi: Option<f64>, volume: f64| {
+            (symbol.to_string(), [rsi, None, Some(volume), Some(0.0)])
+        };
+        let days = BTreeMap::from([
+            (
+                1,
+                vec![
+                    bar("AAPL", Some(70.0), 300.0),
+                    bar("MSFT", Some(50.0), 100.0),
+                    bar("NVDA", Some(30.0), 200.0),
+                    bar("XOM", None, 100.0),
+                ],
+            ),
+            (2, vec![bar("AAPL", Some(60.0), 100.0)]),
+        ]);
+
+        let rows = cross_sectional_features(days);
+        let row = |symbol: &str, timestamp: i64| {
+            rows.iter()
+                .find(|row| row.stock_symbol == symbol && row.event_unix_timestamp == timestamp)
+                .unwrap()
+        };
+
+        let aapl = row("AAPL", 1);
+        assert_eq!(aapl.universe_size, 4);
+        assert_eq!(aapl.rsi.rank, Some(1));
+        assert_eq!(aapl.rsi.percentile, Some(1.0));
+        // RSI of 30, 50 and 70 has a standard deviation of 16.33
+        assert!((aapl.rsi.zscore.unwrap() - 1.2247).abs() < 1e-3);
+        assert_eq!(row("NVDA", 1).rsi.percentile, Some(0.0));
+        assert_eq!(row("XOM", 1).rsi, CrossSectionalScore::default());
+
+        // MSFT and XOM tie on volume
+        let msft = row("MSFT", 1);
+        assert_eq!(
+            (msft.volume.rank, row("XOM", 1).volume.rank),
+            (Some(3), Some(3))
+        );
+        assert!((msft.volume.percentile.unwrap() - 1.0 / 6.0).abs() < 1e-6);
+        assert_eq!(msft.twenty_day_return, CrossSectionalScore::default());
+
+        // Flat or lone values have a rank but no z-score
+        assert_eq!(msft.fifty_day_sma_distance.zscore, None);
+        let alone = row("AAPL", 2);
+        assert_eq!(alone.rsi.rank, Some(1));
+        assert_eq!((alone.rsi.percentile, alone.rsi.zscore), (None, None));
+    }
+}
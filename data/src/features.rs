@@ -0,0 +1,65 @@
+use alpaca_api_client::market_data::stocks::StockBar;
+use database::{BollingerBand, Feature, FeatureSpec, Indicator, PriceField};
+use tindi::{BollingerBands, MovingAverageConvergenceDivergence};
+
+/// Indicator values for the bar at `index`, in the order of the spec's
+/// features. Each indicator looks at the bars before `index` and is `None`
+/// until enough of them exist.
+///
+/// Recomputes every window from scratch; ingestion uses `FeatureEngine`, which
+/// produces the same values incrementally.
+pub fn compute_features(spec: &FeatureSpec, bars: &[StockBar], index: usize) -> Vec<Option<f32>> {
+    spec.features()
+        .iter()
+        .map(|feature| compute_feature(feature, bars, index))
+        .collect()
+}
+
+pub fn source_value(bar: &StockBar, source: PriceField) -> f32 {
+    match source {
+        PriceField::Open => bar.o,
+        PriceField::High => bar.h,
+        PriceField::Low => bar.l,
+        PriceField::Close => bar.c,
+        PriceField::Volume => bar.v,
+    }
+}
+
+fn compute_feature(feature: &Feature, bars: &[StockBar], index: usize) -> Option<f32> {
+    let period = feature.indicator.period();
+    if index < period {
+        return None;
+    }
+
+    let values: Vec<f32> = bars[(index - period)..index]
+        .iter()
+        .map(|bar| source_value(bar, feature.source))
+        .collect();
+
+    let value = match feature.indicator {
+        Indicator::Sma { .. } => tindi::simple_moving_average(&values),
+        Indicator::Ema { .. } => tindi::exponential_moving_average(&values),
+        Indicator::Rsi { .. } => tindi::relative_strength_index(&values),
+        Indicator::High { .. } => tindi::find_high(&values),
+        Indicator::Low { .. } => tindi::find_low(&values),
+        Indicator::Bollinger {
+            band,
+            period,
+            multiplier,
+        } => {
+            let bollinger_bands =
+                BollingerBands::new(&values, period, multiplier).expect("Bollinger Bands failed");
+            match band {
+                BollingerBand::Top => bollinger_bands.top_band,
+                BollingerBand::Middle => bollinger_bands.mid_band,
+                BollingerBand::Bottom => bollinger_bands.bottom_band,
+            }
+        }
+        Indicator::MacdSignal => {
+            MovingAverageConvergenceDivergence::new(&values)
+                .ok()?
+                .signal
+        }
+    };
+    Some(value)
+}
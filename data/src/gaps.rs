@@ -0,0 +1,211 @@
+use std::{collections::BTreeSet, fmt};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Months, NaiveDate};
+use database::{SqliteDb, StockBarsQuery, Timeframe};
+use futures_util::TryStreamExt;
+
+use crate::calendar::{self, Session};
+
+/// A run of consecutive trading sessions with no stored bar for a symbol.
+/// Sessions are exchange days, or the Monday of the week and the first of the
+/// month for weekly and monthly bars.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionGap {
+    pub stock_symbol: String,
+    pub first_missing: NaiveDate,
+    pub last_missing: NaiveDate,
+    pub sessions: usize,
+}
+
+impl SessionGap {
+    /// Days to re-ingest so the gap is filled and the bars on either side get
+    /// their `previous_period_*` and `next_period_*` columns recomputed: from
+    /// the session before the gap through two sessions after it.
+    pub fn refetch_range(&self, timeframe: Timeframe) -> (NaiveDate, NaiveDate) {
+        let store_from = previous_session(timeframe, self.first_missing);
+        let after = next_session(timeframe, self.last_missing);
+        let end = next_session(timeframe, after) + Duration::days(1);
+        (store_from, end)
+    }
+}
+
+impl fmt::Display for SessionGap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.sessions == 1 {
+            write!(
+                f,
+                "{}: {} (1 session)",
+                self.stock_symbol, self.first_missing
+            )
+        } else {
+            write!(
+                f,
+                "{}: {} to {} ({} sessions)",
+                self.stock_symbol, self.first_missing, self.last_missing, self.sessions
+            )
+        }
+    }
+}
+
+/// Finds the sessions between the first and last stored bar of each symbol
+/// that have no bar in the timeframe's table. Every symbol in the table is
+/// checked when `symbols` is empty.
+pub async fn find_gaps(
+    db: &SqliteDb,
+    timeframe: Timeframe,
+    symbols: &[String],
+) -> Result<Vec<SessionGap>> {
+    let symbols = if symbols.is_empty() {
+        db.list_symbols(timeframe).await?
+    } else {
+        symbols.to_vec()
+    };
+
+    let mut gaps = Vec::new();
+    for symbol in symbols {
+        let mut query = StockBarsQuery::new(timeframe)
+            .symbol(&symbol)
+            .columns(&["event_unix_timestamp"]);
+        let mut bars = query.stream(db)?;
+
+        let mut stored = BTreeSet::new();
+        while let Some(bar) = bars.try_next().await? {
+            if let Some(session) = session_of(timeframe, bar.event_unix_timestamp)? {
+                stored.insert(session);
+            }
+        }
+
+        gaps.extend(missing_sessions(&symbol, timeframe, &stored));
+    }
+
+    Ok(gaps)
+}
+
+/// Groups the expected sessions missing from `stored` into runs.
+pub fn missing_sessions(
+    symbol: &str,
+    timeframe: Timeframe,
+    stored: &BTreeSet<NaiveDate>,
+) -> Vec<SessionGap> {
+    let (Some(first), Some(last)) = (stored.first(), stored.last()) else {
+        return Vec::new();
+    };
+
+    let mut gaps: Vec<SessionGap> = Vec::new();
+    let mut in_gap = false;
+    for session in expected_sessions(timeframe, *first, *last) {
+        if stored.contains(&session) {
+            in_gap = false;
+            continue;
+        }
+
+        match gaps.last_mut() {
+            Some(gap) if in_gap => {
+                gap.last_missing = session;
+                gap.sessions += 1;
+            }
+            _ => gaps.push(SessionGap {
+                stock_symbol: symbol.to_string(),
+                first_missing: session,
+                last_missing: session,
+                sessions: 1,
+            }),
+        }
+        in_gap = true;
+    }
+
+    gaps
+}
+
+/// Session a stored bar belongs to. Intraday bars outside the extended
+/// session are ignored.
+fn session_of(timeframe: Timeframe, event_unix_timestamp: i64) -> Result<Option<NaiveDate>> {
+    let datetime = DateTime::from_timestamp_millis(event_unix_timestamp)
+        .ok_or_else(|| anyhow!("Invalid bar timestamp: {}", event_unix_timestamp))?;
+    let day = calendar::exchange_date(datetime);
+
+    Ok(match timeframe {
+        Timeframe::Monthly => Some(calendar::month_start(day)),
+        Timeframe::Weekly => Some(calendar::week_start(day)),
+        Timeframe::Daily => Some(day),
+        Timeframe::Hourly | Timeframe::FifteenMin => {
+            calendar::session_hours(day, Session::Extended)
+                .filter(|(open, close)| (*open..*close).contains(&datetime))
+                .map(|_| day)
+        }
+    })
+}
+
+fn expected_sessions(timeframe: Timeframe, first: NaiveDate, last: NaiveDate) -> Vec<NaiveDate> {
+    match timeframe {
+        Timeframe::Monthly | Timeframe::Weekly => {
+            let mut periods = Vec::new();
+            let mut period = first;
+            while period <= last {
+                let next = next_session(timeframe, period);
+                if !calendar::trading_days(period, next - Duration::days(1)).is_empty() {
+                    periods.push(period);
+                }
+                period = next;
+            }
+            periods
+        }
+        _ => calendar::trading_days(first, last),
+    }
+}
+
+fn previous_session(timeframe: Timeframe, session: NaiveDate) -> NaiveDate {
+    match timeframe {
+        Timeframe::Monthly => session - Months::new(1),
+        Timeframe::Weekly => session - Duration::weeks(1),
+        _ => calendar::previous_trading_day(session),
+    }
+}
+
+fn next_session(timeframe: Timeframe, session: NaiveDate) -> NaiveDate {
+    match timeframe {
+        Timeframe::Monthly => session + Months::new(1),
+        Timeframe::Weekly => session + Duration::weeks(1),
+        _ => calendar::next_trading_day(session),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_sessions_skip_holidays_and_weekends() {
+        let day = |s: &str| s.parse::<NaiveDate>().unwrap();
+        // Friday 2024-03-29 was Good Friday
+        let stored: BTreeSet<NaiveDate> = ["2024-03-26", "2024-03-27", "2024-04-03", "2024-04-05"]
+            .iter()
+            .map(|d| day(d))
+            .collect();
+
+        let gaps = missing_sessions("AAPL", Timeframe::Daily, &stored);
+
+        assert_eq!(
+            gaps,
+            vec![
+                SessionGap {
+                    stock_symbol: "AAPL".to_string(),
+                    first_missing: day("2024-03-28"),
+                    last_missing: day("2024-04-02"),
+                    sessions: 3,
+                },
+                SessionGap {
+                    stock_symbol: "AAPL".to_string(),
+                    first_missing: day("2024-04-04"),
+                    last_missing: day("2024-04-04"),
+                    sessions: 1,
+                },
+            ]
+        );
+        assert_eq!(
+            gaps[0].refetch_range(Timeframe::Daily),
+            (day("2024-03-27"), day("2024-04-05"))
+        );
+    }
+}
@@ -0,0 +1,482 @@
+use std::collections::VecDeque;
+
+use alpaca_api_client::market_data::stocks::StockBar;
+use database::{BollingerBand, FeatureSpec, Indicator, PriceField, MACD_PERIOD};
+use tindi::MovingAverageConvergenceDivergence;
+
+use crate::features::source_value;
+
+/// Incremental version of `compute_features`. Bars are pushed one at a time and
+/// every indicator is updated in constant time, so the same engine serves full
+/// backfills and live bar-by-bar updates.
+///
+/// `values` returns the features of the bar about to be pushed, i.e. computed
+/// over the bars pushed so far, matching `compute_features(spec, bars, index)`
+/// after `bars[..index]` were pushed.
+pub struct FeatureEngine {
+    states: Vec<(PriceField, IndicatorState)>,
+}
+
+impl FeatureEngine {
+    pub fn new(spec: &FeatureSpec) -> Self {
+        let states = spec
+            .features()
+            .iter()
+            .map(|feature| (feature.source, IndicatorState::new(feature.indicator)))
+            .collect();
+
+        Self { states }
+    }
+
+    pub fn push(&mut self, bar: &StockBar) {
+        for (source, state) in &mut self.states {
+            state.push(source_value(bar, *source));
+        }
+    }
+
+    /// `None` for indicators that have not seen enough bars yet.
+    pub fn values(&self) -> Vec<Option<f32>> {
+        self.states.iter().map(|(_, state)| state.value()).collect()
+    }
+}
+
+enum IndicatorState {
+    Sma(RollingSum),
+    Ema(WindowedEma),
+    Rsi(WindowedRsi),
+    High(RollingExtreme),
+    Low(RollingExtreme),
+    Bollinger {
+        moments: RollingSum,
+        band: BollingerBand,
+        multiplier: f64,
+    },
+    MacdSignal(MacdWindow),
+}
+
+impl IndicatorState {
+    fn new(indicator: Indicator) -> Self {
+        match indicator {
+            Indicator::Sma { period } => IndicatorState::Sma(RollingSum::new(period)),
+            Indicator::Ema { period } => IndicatorState::Ema(WindowedEma::new(period)),
+            Indicator::Rsi { period } => IndicatorState::Rsi(WindowedRsi::new(period)),
+            Indicator::High { period } => IndicatorState::High(RollingExtreme::new(period, true)),
+            Indicator::Low { period } => IndicatorState::Low(RollingExtreme::new(period, false)),
+            Indicator::Bollinger {
+                band,
+                period,
+                multiplier,
+            } => IndicatorState::Bollinger {
+                moments: RollingSum::new(period),
+                band,
+                multiplier: multiplier as f64,
+            },
+            Indicator::MacdSignal => IndicatorState::MacdSignal(MacdWindow::new()),
+        }
+    }
+
+    fn push(&mut self, value: f32) {
+        let value = value as f64;
+        match self {
+            IndicatorState::Sma(sum) => sum.push(value),
+            IndicatorState::Ema(ema) => ema.push(value),
+            IndicatorState::Rsi(rsi) => rsi.push(value),
+            IndicatorState::High(extreme) | IndicatorState::Low(extreme) => extreme.push(value),
+            IndicatorState::Bollinger { moments, .. } => moments.push(value),
+            IndicatorState::MacdSignal(macd) => macd.push(value as f32),
+        }
+    }
+
+    fn value(&self) -> Option<f32> {
+        let value = match self {
+            IndicatorState::Sma(sum) => sum.mean()?,
+            IndicatorState::Ema(ema) => ema.value()?,
+            IndicatorState::Rsi(rsi) => rsi.value()?,
+            IndicatorState::High(extreme) | IndicatorState::Low(extreme) => extreme.value()?,
+            IndicatorState::Bollinger {
+                moments,
+                band,
+                multiplier,
+            } => {
+                let mean = moments.mean()?;
+                let deviation = multiplier * moments.std_dev()?;
+                match band {
+                    BollingerBand::Top => mean + deviation,
+                    BollingerBand::Middle => mean,
+                    BollingerBand::Bottom => mean - deviation,
+                }
+            }
+            IndicatorState::MacdSignal(macd) => return macd.signal,
+        };
+        Some(value as f32)
+    }
+}
+
+/// Sum and sum of squares over the last `period` values. Both are re-summed
+/// from the window once per `period` pushes, so the rounding errors of the
+/// running updates cannot build up over long histories.
+struct RollingSum {
+    period: usize,
+    window: VecDeque<f64>,
+    sum: f64,
+    sum_of_squares: f64,
+    pushes_since_resum: usize,
+}
+
+impl RollingSum {
+    fn new(period: usize) -> Self {
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1),
+            sum: 0.0,
+            sum_of_squares: 0.0,
+            pushes_since_resum: 0,
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        self.window.push_back(value);
+        self.sum += value;
+        self.sum_of_squares += value * value;
+        if self.window.len() > self.period {
+            if let Some(evicted) = self.window.pop_front() {
+                self.sum -= evicted;
+                self.sum_of_squares -= evicted * evicted;
+            }
+        }
+
+        self.pushes_since_resum += 1;
+        if self.pushes_since_resum >= self.period {
+            self.pushes_since_resum = 0;
+            self.sum = self.window.iter().sum();
+            self.sum_of_squares = self.window.iter().map(|value| value * value).sum();
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.period > 0 && self.window.len() == self.period
+    }
+
+    fn mean(&self) -> Option<f64> {
+        self.is_full().then(|| self.sum / self.period as f64)
+    }
+
+    /// Population standard deviation of the window.
+    fn std_dev(&self) -> Option<f64> {
+        let mean = self.mean()?;
+        let variance = self.sum_of_squares / self.period as f64 - mean * mean;
+        Some(variance.max(0.0).sqrt())
+    }
+}
+
+/// EMA of the last `period` values, seeded with the oldest value of the window,
+/// which is what `tindi::exponential_moving_average` computes over a slice.
+///
+/// With smoothing `a` and decay `b = 1 - a`, the EMA of `w[0..n]` is
+/// `b^(n-1) * w[0] + a * S` where `S` is the decay-weighted sum of `w[1..n]`.
+/// `S` slides in constant time by decaying it, adding the new value and
+/// removing the term of the value that becomes the new seed.
+struct WindowedEma {
+    period: usize,
+    smoothing: f64,
+    decay: f64,
+    seed_weight: f64,
+    window: VecDeque<f64>,
+    weighted_sum: f64,
+}
+
+impl WindowedEma {
+    fn new(period: usize) -> Self {
+        let smoothing = 2.0 / (period as f64 + 1.0);
+        let decay = 1.0 - smoothing;
+        Self {
+            period,
+            smoothing,
+            decay,
+            seed_weight: decay.powi(period.saturating_sub(1) as i32),
+            window: VecDeque::with_capacity(period + 1),
+            weighted_sum: 0.0,
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        self.window.push_back(value);
+        if self.window.len() > self.period {
+            self.window.pop_front();
+        }
+
+        self.weighted_sum = self.decay * self.weighted_sum + value;
+        if self.window.len() == self.period {
+            if let Some(seed) = self.window.front() {
+                self.weighted_sum -= self.seed_weight * seed;
+            }
+        }
+    }
+
+    fn value(&self) -> Option<f64> {
+        if self.period == 0 || self.window.len() < self.period {
+            return None;
+        }
+        let seed = self.window.front()?;
+        Some(self.seed_weight * seed + self.smoothing * self.weighted_sum)
+    }
+}
+
+/// RSI over the price changes inside the last `period` values, matching
+/// `tindi::relative_strength_index` over a slice. Gains and losses are kept as
+/// running sums, so the averaging divisor cancels out of `RS`.
+///
+/// This is the simple-average (Cutler) RSI, not Wilder's: gains and losses are
+/// plain means over the window instead of being smoothed recursively over the
+/// whole history, so values differ from charting tools that use Wilder's RSI.
+struct WindowedRsi {
+    period: usize,
+    seen: usize,
+    previous: Option<f64>,
+    changes: VecDeque<f64>,
+    gains: f64,
+    losses: f64,
+}
+
+impl WindowedRsi {
+    fn new(period: usize) -> Self {
+        Self {
+            period,
+            seen: 0,
+            previous: None,
+            changes: VecDeque::with_capacity(period),
+            gains: 0.0,
+            losses: 0.0,
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        self.seen += 1;
+        if let Some(previous) = self.previous.replace(value) {
+            let change = value - previous;
+            self.changes.push_back(change);
+            self.gains += change.max(0.0);
+            self.losses += (-change).max(0.0);
+
+            if self.changes.len() + 1 > self.period {
+                if let Some(evicted) = self.changes.pop_front() {
+                    self.gains -= evicted.max(0.0);
+                    self.losses -= (-evicted).max(0.0);
+                }
+            }
+        }
+    }
+
+    fn value(&self) -> Option<f64> {
+        if self.period == 0 || self.seen < self.period {
+            return None;
+        }
+        let losses = self.losses.max(0.0);
+        if losses == 0.0 {
+            return Some(100.0);
+        }
+        let relative_strength = self.gains.max(0.0) / losses;
+        Some(100.0 - 100.0 / (1.0 + relative_strength))
+    }
+}
+
+/// Highest or lowest of the last `period` values via a monotonic deque, so each
+/// value is pushed and popped at most once.
+struct RollingExtreme {
+    period: usize,
+    highest: bool,
+    index: usize,
+    candidates: VecDeque<(usize, f64)>,
+}
+
+impl RollingExtreme {
+    fn new(period: usize, highest: bool) -> Self {
+        Self {
+            period,
+            highest,
+            index: 0,
+            candidates: VecDeque::with_capacity(period),
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        while let Some(&(_, back)) = self.candidates.back() {
+            let dominated = if self.highest {
+                back <= value
+            } else {
+                back >= value
+            };
+            if !dominated {
+                break;
+            }
+            self.candidates.pop_back();
+        }
+        self.candidates.push_back((self.index, value));
+        self.index += 1;
+
+        while let Some(&(front_index, _)) = self.candidates.front() {
+            if front_index + self.period >= self.index {
+                break;
+            }
+            self.candidates.pop_front();
+        }
+    }
+
+    fn value(&self) -> Option<f64> {
+        if self.period == 0 || self.index < self.period {
+            return None;
+        }
+        self.candidates.front().map(|&(_, value)| value)
+    }
+}
+
+/// The MACD signal depends on the full EMA chain over its fixed 35-bar window,
+/// so it is recomputed with `tindi` from a ring buffer on every push, which
+/// keeps it identical to `compute_features`. The work per bar is bounded by the
+/// window and nothing is allocated.
+struct MacdWindow {
+    window: VecDeque<f32>,
+    signal: Option<f32>,
+}
+
+impl MacdWindow {
+    fn new() -> Self {
+        Self {
+            window: VecDeque::with_capacity(MACD_PERIOD + 1),
+            signal: None,
+        }
+    }
+
+    fn push(&mut self, value: f32) {
+        self.window.push_back(value);
+        if self.window.len() > MACD_PERIOD {
+            self.window.pop_front();
+        }
+        if self.window.len() == MACD_PERIOD {
+            let prices = self.window.make_contiguous();
+            self.signal = MovingAverageConvergenceDivergence::new(prices)
+                .ok()
+                .map(|macd| macd.signal);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use database::Timeframe;
+
+    use super::*;
+    use crate::features::compute_features;
+
+    fn synthetic_bars(count: usize) -> Vec<StockBar> {
+        (0..count)
+            .map(|index| {
+                let step = index as f32;
+                let close = 100.0 + 10.0 * (step / 7.0).sin() + step * 0.05;
+                let open = close - (step / 3.0).cos();
+                serde_json::from_value(serde_json::json!({
+                    "t": "2024-01-02T05:00:00Z",
+                    "o": open,
+                    "h": open.max(close) + 1.5,
+                    "l": open.min(close) - 1.5,
+                    "c": close,
+                    "v": 1_000_000.0 + 1000.0 * step,
+                    "n": 100,
+                    "vw": (open + close) / 2.0,
+                }))
+                .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_feature_engine_matches_windowed_tindi_features() {
+        let bars = synthetic_bars(300);
+        for timeframe in Timeframe::ALL {
+            let spec = timeframe.feature_spec();
+            let mut engine = FeatureEngine::new(&spec);
+            for (index, bar) in bars.iter().enumerate() {
+                let expected = compute_features(&spec, &bars, index);
+                for (column, (actual, expected)) in spec
+                    .columns()
+                    .iter()
+                    .zip(engine.values().into_iter().zip(expected))
+                {
+                    match (actual, expected) {
+                        (Some(actual), Some(expected)) => {
+                            let tolerance = 1e-3 * expected.abs().max(1.0);
+                            assert!(
+                                (actual - expected).abs() <= tolerance,
+                                "{} at bar {}: {} != {}",
+                                column,
+                                index,
+                                actual,
+                                expected
+                            );
+                        }
+                        (actual, expected) => assert_eq!(
+                            actual.is_some(),
+                            expected.is_some(),
+                            "{} warm-up at bar {}",
+                            column,
+                            index
+                        ),
+                    }
+                }
+                engine.push(bar);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rolling_sum_and_windowed_ema_do_not_drift() {
+        let period = 20;
+        let values: Vec<f64> = (0..200_000)
+            .map(|index| {
+                let step = index as f64;
+                100.0 + 10.0 * (step / 7.0).sin() + step * 0.05
+            })
+            .collect();
+        let mut sum = RollingSum::new(period);
+        let mut ema = WindowedEma::new(period);
+        for value in &values {
+            sum.push(*value);
+            ema.push(*value);
+        }
+
+        let window = &values[values.len() - period..];
+        let mean = window.iter().sum::<f64>() / period as f64;
+        let std_dev = (window
+            .iter()
+            .map(|value| (value - mean).powi(2))
+            .sum::<f64>()
+            / period as f64)
+            .sqrt();
+        let smoothing = 2.0 / (period as f64 + 1.0);
+        let expected_ema = window[1..]
+            .iter()
+            .fold(window[0], |ema, value| ema + smoothing * (value - ema));
+
+        assert!((sum.mean().unwrap() - mean).abs() <= 1e-9 * mean);
+        assert!((sum.std_dev().unwrap() - std_dev).abs() <= 1e-6);
+        assert!((ema.value().unwrap() - expected_ema).abs() <= 1e-9 * expected_ema);
+    }
+
+    #[test]
+    fn test_feature_engine_macd_signal_equals_tindi() {
+        let bars = synthetic_bars(300);
+        let closes: Vec<f32> = bars.iter().map(|bar| bar.c).collect();
+        let spec = FeatureSpec::new().macd_signal("macd_signal", PriceField::Close);
+        let mut engine = FeatureEngine::new(&spec);
+        for (index, bar) in bars.iter().enumerate() {
+            let expected = (index >= MACD_PERIOD).then(|| {
+                MovingAverageConvergenceDivergence::new(&closes[index - MACD_PERIOD..index])
+                    .unwrap()
+                    .signal
+            });
+
+            assert_eq!(engine.values(), vec![expected], "bar {}", index);
+            engine.push(bar);
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, NaiveDate};
+use database::{
+    AdjustmentMode, BarRepository, FeatureSpec, SqliteDb, StockBarModelEntry, Timeframe,
+};
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+use crate::adjustments::adjust_bars;
+use crate::calendar;
+use crate::indicators::FeatureEngine;
+use crate::sources::BarSource;
+
+pub struct IngestOptions {
+    pub start: String,
+    pub end: Option<String>,
+    /// Only fetch and store bars newer than the latest stored bar of each symbol.
+    pub catch_up: bool,
+    /// Corporate-action adjustment applied to prices before features are computed.
+    pub adjustment: AdjustmentMode,
+    /// Only store bars from this exchange day on. Earlier fetched bars just warm
+    /// up the indicators, so a stored range can be re-ingested in place.
+    pub store_from: Option<NaiveDate>,
+    /// Indicator columns to compute, usually `Config::feature_spec`.
+    pub feature_spec: FeatureSpec,
+}
+
+pub async fn insert_stock_bars(
+    db: &SqliteDb,
+    source: &impl BarSource,
+    timeframe: Timeframe,
+    symbols: Vec<&str>,
+    options: &IngestOptions,
+) -> Result<()> {
+    let spec = &options.feature_spec;
+    for statement in db.sync_feature_columns(timeframe, spec).await? {
+        println!("Added feature column: {}", statement);
+    }
+
+    let warm_up = warm_up(timeframe, spec.warm_up_bars());
+    let window = FetchWindow::new(db, timeframe.table(), &symbols, options, warm_up).await?;
+
+    println!("Fetching historical stock data");
+    let bars_map = fetch_blocking(|| {
+        source.fetch_bars(&symbols, timeframe, &window.start, options.end.as_deref())
+    })?;
+
+    println!("Finished fetching historical stock data");
+
+    println!("Inserting stock data");
+    for (symbol, mut bars) in bars_map {
+        if options.adjustment != AdjustmentMode::Raw {
+            let actions = db.corporate_actions(&symbol).await?;
+            adjust_bars(&mut bars, &actions, options.adjustment)?;
+        }
+
+        let mut stock_bar_entries = Vec::new();
+        let mut engine = FeatureEngine::new(spec);
+        for (index, bar) in bars.iter().enumerate() {
+            if index + 1 == bars.len() {
+                break;
+            }
+
+            let features = engine.values();
+            engine.push(bar);
+
+            let previous_bar = index.checked_sub(1).map(|previous| &bars[previous]);
+            let next_bar = &bars[index + 1];
+
+            let mut entry = StockBarModelEntry::new(
+                bar,
+                previous_bar,
+                next_bar,
+                &symbol,
+                timeframe.alpaca_timeframe(),
+                options.adjustment,
+                features,
+            )?;
+            entry.provenance = source.provenance().to_string();
+
+            stock_bar_entries.push(entry);
+        }
+        stock_bar_entries.retain(|entry| window.is_new(&symbol, entry.event_unix_timestamp));
+
+        let report = db
+            .upsert_batch_of_stock_bars(timeframe, spec, &stock_bar_entries)
+            .await?;
+
+        println!("Insertion completed for stock: {} ({})", symbol, report);
+    }
+
+    Ok(())
+}
+
+/// Sources block while fetching, so the fetch is moved off the async worker
+/// threads when the runtime has more than one.
+fn fetch_blocking<T>(fetch: impl FnOnce() -> T) -> T {
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(fetch)
+        }
+        _ => fetch(),
+    }
+}
+
+/// Calendar time covering `bars` bars of a timeframe, with room for weekends,
+/// holidays and short sessions.
+pub(crate) fn warm_up(timeframe: Timeframe, bars: usize) -> Duration {
+    let bars = bars as i64;
+    match timeframe {
+        Timeframe::Monthly => Duration::days((bars + 2) * 31),
+        Timeframe::Weekly => Duration::weeks(bars + 2),
+        Timeframe::Daily => Duration::days(bars * 7 / 5 + 14),
+        Timeframe::Hourly => Duration::days(bars / 7 + 5),
+        Timeframe::FifteenMin => Duration::days(bars / 26 + 5),
+    }
+}
+
+/// Range of bars to fetch for an ingestion run. In catch-up mode the start is
+/// pulled back far enough before the oldest of the latest stored bars to warm up
+/// the indicator windows, and only bars newer than what is stored are kept.
+/// The bar that was skipped last time for lacking a next bar is re-fetched and
+/// stored with its labels. Stored bars were adjusted without a corporate action
+/// that goes ex after them, so a symbol with one is fetched and stored again
+/// from `start` like a new symbol. With `store_from` the start is pulled back
+/// the same way before that day and only bars from it on are kept.
+struct FetchWindow {
+    start: String,
+    latest_stored: HashMap<String, i64>,
+    store_from: Option<i64>,
+}
+
+impl FetchWindow {
+    async fn new(
+        db: &SqliteDb,
+        table: &str,
+        symbols: &[&str],
+        options: &IngestOptions,
+        warm_up: Duration,
+    ) -> Result<Self> {
+        if !options.catch_up {
+            let Some(store_from) = options.store_from else {
+                return Ok(Self {
+                    start: options.start.clone(),
+                    latest_stored: HashMap::new(),
+                    store_from: None,
+                });
+            };
+
+            let store_from = calendar::exchange_midnight(store_from).timestamp_millis();
+            return Ok(Self {
+                start: warm_up_start(store_from, warm_up)?,
+                latest_stored: HashMap::new(),
+                store_from: Some(store_from),
+            });
+        }
+
+        let mut latest_stored: HashMap<String, i64> = db
+            .latest_event_unix_timestamps(table)
+            .await?
+            .into_iter()
+            .filter(|(symbol, _)| symbols.contains(&symbol.as_str()))
+            .collect();
+
+        if options.adjustment != AdjustmentMode::Raw {
+            for symbol in readjusted_symbols(db, &latest_stored).await? {
+                println!(
+                    "{} has a corporate action after its stored bars, re-adjusting its history",
+                    symbol
+                );
+                latest_stored.remove(&symbol);
+            }
+        }
+
+        // Symbols with nothing stored yet still need their full history
+        let has_new_symbols = symbols
+            .iter()
+            .any(|symbol| !latest_stored.contains_key(*symbol));
+
+        let start = match latest_stored.values().min() {
+            Some(oldest_latest) if !has_new_symbols => warm_up_start(*oldest_latest, warm_up)?,
+            _ => options.start.clone(),
+        };
+
+        Ok(Self {
+            start,
+            latest_stored,
+            store_from: None,
+        })
+    }
+
+    fn is_new(&self, symbol: &str, event_unix_timestamp: i64) -> bool {
+        if self
+            .store_from
+            .is_some_and(|store_from| event_unix_timestamp < store_from)
+        {
+            return false;
+        }
+
+        match self.latest_stored.get(symbol) {
+            Some(latest) => event_unix_timestamp > *latest,
+            None => true,
+        }
+    }
+}
+
+/// Symbols with a corporate action going ex after the day of their latest
+/// stored bar.
+async fn readjusted_symbols(
+    db: &SqliteDb,
+    latest_stored: &HashMap<String, i64>,
+) -> Result<Vec<String>> {
+    let mut symbols = Vec::new();
+    for (symbol, latest) in latest_stored {
+        let stored_through = DateTime::from_timestamp_millis(*latest)
+            .map(calendar::exchange_date)
+            .ok_or_else(|| anyhow!("Invalid stored timestamp for {}: {}", symbol, latest))?;
+        let actions = db.corporate_actions(symbol).await?;
+        if actions.iter().any(|action| action.ex_date > stored_through) {
+            symbols.push(symbol.clone());
+        }
+    }
+    Ok(symbols)
+}
+
+/// Day to start fetching so the bars from `from` on have warmed-up indicators.
+fn warm_up_start(from: i64, warm_up: Duration) -> Result<String> {
+    let start = from - warm_up.num_milliseconds();
+    Ok(DateTime::from_timestamp_millis(start)
+        .ok_or_else(|| anyhow!("Invalid fetch start timestamp: {}", start))?
+        .format("%Y-%m-%d")
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use alpaca_api_client::market_data::stocks::StockBar;
+    use database::{CorporateAction, CorporateActionType, StockBarsQuery};
+    use futures_util::TryStreamExt;
+
+    use super::*;
+
+    /// Daily bars before and after a 2-for-1 split going ex on 2024-06-07.
+    struct SplitSource;
+
+    impl BarSource for SplitSource {
+        fn fetch_bars(
+            &self,
+            symbols: &[&str],
+            _timeframe: Timeframe,
+            start: &str,
+            end: Option<&str>,
+        ) -> Result<HashMap<String, Vec<StockBar>>> {
+            let bars = [
+                ("2024-06-03T04:00:00Z", 200.0),
+                ("2024-06-04T04:00:00Z", 200.0),
+                ("2024-06-05T04:00:00Z", 200.0),
+                ("2024-06-06T04:00:00Z", 200.0),
+                ("2024-06-07T04:00:00Z", 100.0),
+                ("2024-06-10T04:00:00Z", 100.0),
+            ]
+            .into_iter()
+            .filter(|(t, _)| *t >= start && !end.is_some_and(|end| t[..10] > *end))
+            .map(|(t, c)| {
+                serde_json::from_value(serde_json::json!({
+                    "t": t, "o": c, "h": c, "l": c, "c": c, "v": 100.0, "n": 1, "vw": c,
+                }))
+            })
+            .collect::<serde_json::Result<Vec<StockBar>>>()?;
+            Ok(HashMap::from([(symbols[0].to_string(), bars)]))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_catch_up_re_adjusts_history_after_a_new_split() {
+        let db = SqliteDb::create_new("sqlite::memory:").await.unwrap();
+        let mut options = IngestOptions {
+            start: "2024-06-03".to_string(),
+            // Ingested before the split was announced
+            end: Some("2024-06-06".to_string()),
+            catch_up: false,
+            adjustment: AdjustmentMode::Split,
+            store_from: None,
+            feature_spec: Timeframe::Daily.feature_spec(),
+        };
+        let source = SplitSource;
+
+        insert_stock_bars(&db, &source, Timeframe::Daily, vec!["AAPL"], &options)
+            .await
+            .unwrap();
+        db.upsert_corporate_actions(&[CorporateAction {
+            stock_symbol: "AAPL".to_string(),
+            action_type: CorporateActionType::Split,
+            ex_date: "2024-06-07".parse().unwrap(),
+            split_ratio: Some(2.0),
+            cash_amount: None,
+        }])
+        .await
+        .unwrap();
+
+        options.end = None;
+        options.catch_up = true;
+        insert_stock_bars(&db, &source, Timeframe::Daily, vec!["AAPL"], &options)
+            .await
+            .unwrap();
+
+        let closes: Vec<f32> = StockBarsQuery::new(Timeframe::Daily)
+            .columns(&["stock_symbol", "event_unix_timestamp", "close_price"])
+            .stream(&db)
+            .unwrap()
+            .map_ok(|bar| bar.close_price)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(closes, vec![100.0; 5]);
+    }
+}
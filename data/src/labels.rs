@@ -0,0 +1,227 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate};
+use database::{ForwardReturnLabel, LabelDirection, SqliteDb, StockBarsQuery, Timeframe};
+
+use crate::calendar;
+use crate::relative::growth;
+use crate::LabelConfig;
+
+/// The prices of a bar that its labels are measured from and to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct LabelBar {
+    timestamp: i64,
+    close: f64,
+    high: f64,
+    low: f64,
+}
+
+/// Labels every bar of a timeframe's table from `from` on at each configured
+/// horizon with enough later bars. Without `from` each symbol continues from
+/// the oldest of its horizons' latest labels, which fills in bars that only now
+/// have enough bars ahead. Returns how many rows were written.
+pub async fn update_forward_return_labels(
+    db: &SqliteDb,
+    timeframe: Timeframe,
+    config: &LabelConfig,
+    from: Option<NaiveDate>,
+) -> Result<u64> {
+    let from = from.map(|from| calendar::exchange_midnight(from).timestamp_millis());
+    let latest = db.latest_label_timestamps(timeframe).await?;
+    let mut symbols: Vec<String> = db
+        .latest_event_unix_timestamps(timeframe.table())
+        .await?
+        .into_keys()
+        .collect();
+    symbols.sort();
+
+    let mut written = 0;
+    for stock_symbol in symbols {
+        // A horizon without labels yet is labelled from the symbol's first bar
+        let start = from.or_else(|| {
+            config
+                .horizons
+                .iter()
+                .map(|horizon| {
+                    latest
+                        .get(&(stock_symbol.clone(), *horizon as i64))
+                        .copied()
+                })
+                .collect::<Option<Vec<i64>>>()
+                .and_then(|latest| latest.into_iter().min())
+        });
+
+        let mut query = StockBarsQuery::new(timeframe)
+            .symbol(&stock_symbol)
+            .columns(&[
+                "stock_symbol",
+                "event_unix_timestamp",
+                "close_price",
+                "high_price",
+                "low_price",
+            ]);
+        if let Some(start) = start.and_then(DateTime::from_timestamp_millis) {
+            query = query.start(start);
+        }
+
+        // Loaded up front so the writes below do not wait on an open read
+        let bars: Vec<LabelBar> = query
+            .fetch_all(db)
+            .await?
+            .into_iter()
+            .map(|bar| LabelBar {
+                timestamp: bar.event_unix_timestamp,
+                close: f64::from(bar.close_price),
+                high: f64::from(bar.high_price),
+                low: f64::from(bar.low_price),
+            })
+            .collect();
+
+        let labels = forward_return_labels(&stock_symbol, &bars, config);
+        written += db.upsert_forward_return_labels(timeframe, &labels).await?;
+    }
+    Ok(written)
+}
+
+/// Labels of a symbol's bars, oldest first, skipping bars without enough bars
+/// ahead for a horizon.
+fn forward_return_labels(
+    stock_symbol: &str,
+    bars: &[LabelBar],
+    config: &LabelConfig,
+) -> Vec<ForwardReturnLabel> {
+    let mut labels = Vec::new();
+    for (index, bar) in bars.iter().enumerate() {
+        for &horizon in &config.horizons {
+            let Some(ahead) = bars.get(index + 1..=index + horizon) else {
+                continue;
+            };
+            let high = ahead.iter().map(|bar| bar.high).fold(f64::MIN, f64::max);
+            let low = ahead.iter().map(|bar| bar.low).fold(f64::MAX, f64::min);
+            let (Some(forward), Some(run_up), Some(drawdown)) = (
+                growth(bar.close, ahead[horizon - 1].close),
+                growth(bar.close, high),
+                growth(bar.close, low),
+            ) else {
+                continue;
+            };
+
+            let forward_return = forward - 1.0;
+            let direction = if forward_return > config.dead_zone {
+                LabelDirection::Up
+            } else if forward_return < -config.dead_zone {
+                LabelDirection::Down
+            } else {
+                LabelDirection::Flat
+            };
+            labels.push(ForwardReturnLabel {
+                stock_symbol: stock_symbol.to_string(),
+                event_unix_timestamp: bar.timestamp,
+                horizon_bars: horizon as i64,
+                forward_return: forward_return as f32,
+                max_run_up: (run_up - 1.0) as f32,
+                max_drawdown: (drawdown - 1.0) as f32,
+                dead_zone: config.dead_zone as f32,
+                direction,
+            });
+        }
+    }
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use database::AdjustmentMode;
+
+    use super::*;
+    use crate::test_support::FixedBarSource;
+    use crate::{insert_stock_bars, IngestOptions};
+
+    #[test]
+    fn test_forward_return_labels_per_horizon() {
+        let bars: Vec<LabelBar> = [100.0, 103.0, 99.0, 100.2]
+            .iter()
+            .enumerate()
+            .map(|(index, close)| LabelBar {
+                timestamp: index as i64,
+                close: *close,
+                high: close + 1.0,
+                low: close - 1.0,
+            })
+            .collect();
+        let config = LabelConfig {
+            horizons: vec![1, 3],
+            dead_zone: 0.005,
+        };
+
+        let labels = forward_return_labels("AAPL", &bars, &config);
+        let label = |timestamp: i64, horizon: i64| {
+            labels
+                .iter()
+                .find(|label| {
+                    label.event_unix_timestamp == timestamp && label.horizon_bars == horizon
+                })
+                .unwrap()
+        };
+
+        // Three one-bar labels and one three-bar label
+        assert_eq!(labels.len(), 4);
+        assert_eq!(label(0, 1).direction, LabelDirection::Up);
+        assert_eq!(label(1, 1).direction, LabelDirection::Down);
+
+        // 100.2 is within the dead zone of 100, after a high of 104 and a low of 98
+        let three_bars = label(0, 3);
+        assert!((three_bars.forward_return - 0.002).abs() < 1e-6);
+        assert!((three_bars.max_run_up - 0.04).abs() < 1e-6);
+        assert!((three_bars.max_drawdown + 0.02).abs() < 1e-6);
+        assert_eq!(three_bars.direction, LabelDirection::Flat);
+    }
+
+    #[tokio::test]
+    async fn test_symbols_added_later_are_labelled_from_their_first_bar() {
+        let db = SqliteDb::create_new("sqlite::memory:").await.unwrap();
+        let source = FixedBarSource(vec![
+            ("2024-06-03T04:00:00Z", 100.0),
+            ("2024-06-04T04:00:00Z", 101.0),
+            ("2024-06-05T04:00:00Z", 102.0),
+            ("2024-06-06T04:00:00Z", 103.0),
+            ("2024-06-07T04:00:00Z", 104.0),
+            ("2024-06-10T04:00:00Z", 105.0),
+        ]);
+        let options = IngestOptions {
+            start: "2024-06-03".to_string(),
+            end: None,
+            catch_up: false,
+            adjustment: AdjustmentMode::Raw,
+            store_from: None,
+            feature_spec: Timeframe::Daily.feature_spec(),
+        };
+        let config = LabelConfig {
+            horizons: vec![1, 2],
+            dead_zone: 0.005,
+        };
+
+        insert_stock_bars(&db, &source, Timeframe::Daily, vec!["AAPL"], &options)
+            .await
+            .unwrap();
+        update_forward_return_labels(&db, Timeframe::Daily, &config, None)
+            .await
+            .unwrap();
+        insert_stock_bars(&db, &source, Timeframe::Daily, vec!["MSFT"], &options)
+            .await
+            .unwrap();
+        update_forward_return_labels(&db, Timeframe::Daily, &config, None)
+            .await
+            .unwrap();
+
+        // Five stored bars, four with a bar one ahead and three with two
+        for stock_symbol in ["AAPL", "MSFT"] {
+            for (horizon, count) in [(1, 4), (2, 3)] {
+                let labels = db
+                    .forward_return_labels(stock_symbol, Timeframe::Daily, horizon)
+                    .await
+                    .unwrap();
+                assert_eq!(labels.len(), count, "{} at {} bars", stock_symbol, horizon);
+            }
+        }
+    }
+}
@@ -0,0 +1,37 @@
+pub mod calendar;
+pub mod watchlist;
+
+mod adjustments;
+pub use adjustments::*;
+
+mod config;
+pub use config::*;
+
+mod cross_sectional;
+pub use cross_sectional::*;
+
+mod features;
+pub use features::*;
+
+mod gaps;
+pub use gaps::*;
+
+mod indicators;
+pub use indicators::*;
+
+mod ingest;
+pub use ingest::*;
+
+mod labels;
+pub use labels::*;
+
+mod reference;
+pub use reference::*;
+
+mod relative;
+pub use relative::*;
+
+pub mod sources;
+
+#[cfg(test)]
+mod test_support;
@@ -0,0 +1,35 @@
+use std::{fs::File, path::Path};
+
+use anyhow::{bail, Context, Result};
+use database::SymbolReference;
+
+/// Reads ticker reference data from a CSV file with a
+/// `ticker,name,asset_class,sector,benchmark_etf,exchange,active` header, or
+/// from a JSON array of objects with the same fields. Every field but the
+/// ticker may be empty or left out.
+pub fn load_symbols(path: &Path) -> Result<Vec<SymbolReference>> {
+    let symbols: Vec<SymbolReference> = match path.extension().and_then(|e| e.to_str()) {
+        Some("csv") => {
+            let mut reader = csv::Reader::from_path(path)?;
+            reader
+                .deserialize()
+                .enumerate()
+                .map(|(index, symbol)| {
+                    symbol.with_context(|| format!("{}:{}", path.display(), index + 2))
+                })
+                .collect::<Result<_>>()?
+        }
+        Some("json") => serde_json::from_reader(File::open(path)?)
+            .with_context(|| format!("Invalid symbols in {}", path.display()))?,
+        _ => bail!("Expected a .csv or .json file: {}", path.display()),
+    };
+
+    if let Some(index) = symbols
+        .iter()
+        .position(|symbol| symbol.ticker.trim().is_empty())
+    {
+        bail!("Symbol {} in {} has no ticker", index + 1, path.display());
+    }
+
+    Ok(symbols)
+}
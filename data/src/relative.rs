@@ -0,0 +1,490 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate};
+use database::{AssetClass, RelativeFeatures, SqliteDb, StockBarsQuery, Symbol, Timeframe};
+use futures_util::TryStreamExt;
+
+use crate::calendar;
+use crate::ingest::warm_up;
+
+/// Benchmark of every symbol for relative strength against the market.
+pub const MARKET_BENCHMARK: &str = "SPY";
+
+/// Closing prices of a symbol by `event_unix_timestamp`.
+type Closes = BTreeMap<i64, f64>;
+
+/// Lookbacks of the relative features, in bars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelativeLookback {
+    /// Bars spanned by the returns behind relative strength and sector rank.
+    pub return_bars: usize,
+    /// One-bar returns in the rolling beta and correlation.
+    pub beta_bars: usize,
+}
+
+impl RelativeLookback {
+    pub fn for_timeframe(timeframe: Timeframe) -> Self {
+        let (return_bars, beta_bars) = match timeframe {
+            Timeframe::Monthly => (12, 24),
+            Timeframe::Weekly => (13, 52),
+            Timeframe::Daily => (20, 60),
+            // One and five extended-hours sessions
+            Timeframe::Hourly => (16, 80),
+            Timeframe::FifteenMin => (64, 320),
+        };
+        Self {
+            return_bars,
+            beta_bars,
+        }
+    }
+}
+
+/// Recomputes the sector-relative features of `symbols` once ingestion has
+/// loaded them, and stores those of bars from `from` on. Without `from` each
+/// symbol continues from its own latest stored row, or from its first bar when
+/// it has none. Sectors and benchmark ETFs come from the `symbols` table, so
+/// the sector ETFs and SPY need bars in the same table. Returns how many rows
+/// were written.
+pub async fn update_relative_features(
+    db: &SqliteDb,
+    timeframe: Timeframe,
+    symbols: &[&str],
+    from: Option<NaiveDate>,
+) -> Result<u64> {
+    let lookback = RelativeLookback::for_timeframe(timeframe);
+    let from: HashMap<String, Option<i64>> = match from {
+        Some(from) => {
+            let from = calendar::exchange_midnight(from).timestamp_millis();
+            symbols
+                .iter()
+                .map(|symbol| (symbol.to_string(), Some(from)))
+                .collect()
+        }
+        None => {
+            let latest = db.latest_relative_features_timestamps(timeframe).await?;
+            symbols
+                .iter()
+                .map(|symbol| (symbol.to_string(), latest.get(*symbol).copied()))
+                .collect()
+        }
+    };
+
+    let reference: HashMap<String, Symbol> = db
+        .symbols(None)
+        .await?
+        .into_iter()
+        .map(|symbol| (symbol.ticker.clone(), symbol))
+        .collect();
+
+    // Earlier bars only warm up the lookbacks
+    let warm_up = warm_up(timeframe, lookback.return_bars.max(lookback.beta_bars) + 1);
+    let mut closes: HashMap<String, Closes> = HashMap::new();
+    for (ticker, load_from) in load_ranges(&from, &reference) {
+        let mut query = StockBarsQuery::new(timeframe).symbol(&ticker).columns(&[
+            "stock_symbol",
+            "event_unix_timestamp",
+            "close_price",
+        ]);
+        if let Some(load_from) = load_from
+            .and_then(|from| DateTime::from_timestamp_millis(from - warm_up.num_milliseconds()))
+        {
+            query = query.start(load_from);
+        }
+
+        let mut bars = query.stream(db)?;
+        while let Some(bar) = bars.try_next().await? {
+            closes
+                .entry(bar.stock_symbol)
+                .or_default()
+                .insert(bar.event_unix_timestamp, f64::from(bar.close_price));
+        }
+    }
+
+    let from = from
+        .into_iter()
+        .map(|(ticker, from)| (ticker, from.unwrap_or(i64::MIN)))
+        .collect();
+    let rows = relative_features(&closes, &reference, lookback, &from);
+    db.upsert_relative_features(timeframe, &rows).await
+}
+
+/// Symbols whose bars the features of the symbols in `from` are computed
+/// from: the symbols themselves, the other stocks of their sector, their
+/// sector ETF and SPY. Each maps to the earliest bar it is needed for, `None`
+/// for its whole history.
+fn load_ranges(
+    from: &HashMap<String, Option<i64>>,
+    reference: &HashMap<String, Symbol>,
+) -> HashMap<String, Option<i64>> {
+    let mut ranges: HashMap<String, Option<i64>> = HashMap::new();
+    let mut need = |ticker: &str, from: Option<i64>| {
+        ranges
+            .entry(ticker.to_string())
+            .and_modify(|earliest| *earliest = earliest.zip(from).map(|(a, b)| a.min(b)))
+            .or_insert(from);
+    };
+
+    for (ticker, from) in from {
+        need(ticker, *from);
+        need(MARKET_BENCHMARK, *from);
+        let Some(symbol) = reference.get(ticker) else {
+            continue;
+        };
+        if let Some(etf) = &symbol.benchmark_etf {
+            need(etf, *from);
+        }
+        if symbol.asset_class != AssetClass::Stock || symbol.sector.is_none() {
+            continue;
+        }
+        for peer in reference
+            .values()
+            .filter(|peer| peer.asset_class == AssetClass::Stock && peer.sector == symbol.sector)
+        {
+            need(&peer.ticker, *from);
+        }
+    }
+    ranges
+}
+
+/// Features of the bars of every symbol in `from` from its timestamp on, by
+/// symbol and then time.
+fn relative_features(
+    closes: &HashMap<String, Closes>,
+    symbols: &HashMap<String, Symbol>,
+    lookback: RelativeLookback,
+    from: &HashMap<String, i64>,
+) -> Vec<RelativeFeatures> {
+    // Lookback returns of each sector's stocks, for the percentile ranks
+    let sector_of = |ticker: &str| {
+        symbols
+            .get(ticker)
+            .filter(|symbol| symbol.asset_class == AssetClass::Stock)
+            .and_then(|symbol| symbol.sector.as_deref())
+    };
+    let mut sector_returns: HashMap<&str, HashMap<i64, Vec<f64>>> = HashMap::new();
+    for (ticker, prices) in closes {
+        let Some(sector) = sector_of(ticker.as_str()) else {
+            continue;
+        };
+        for (timestamp, growth) in lookback_growth(prices, lookback.return_bars) {
+            sector_returns
+                .entry(sector)
+                .or_default()
+                .entry(timestamp)
+                .or_default()
+                .push(growth);
+        }
+    }
+    for returns in sector_returns.values_mut().flat_map(HashMap::values_mut) {
+        returns.sort_by(f64::total_cmp);
+    }
+
+    let mut tickers: Vec<(&String, i64)> = from
+        .iter()
+        .filter(|(ticker, _)| closes.contains_key(*ticker))
+        .map(|(ticker, from)| (ticker, *from))
+        .collect();
+    tickers.sort();
+
+    let mut rows = Vec::new();
+    for (ticker, from) in tickers {
+        let prices: Vec<(i64, f64)> = closes[ticker].iter().map(|(t, c)| (*t, *c)).collect();
+        let benchmark_of = |benchmark: &str| {
+            (benchmark != ticker.as_str())
+                .then(|| closes.get(benchmark))
+                .flatten()
+        };
+        let sector_etf = symbols
+            .get(ticker)
+            .and_then(|symbol| symbol.benchmark_etf.as_deref())
+            .and_then(|etf| Some((etf, benchmark_of(etf)?)));
+        let market = benchmark_of(MARKET_BENCHMARK);
+        let benchmark = sector_etf.or(market.map(|market| (MARKET_BENCHMARK, market)));
+        let sector_ranks = sector_of(ticker.as_str()).and_then(|sector| sector_returns.get(sector));
+
+        let mut regression = RollingRegression::new(lookback.beta_bars);
+        for (index, (timestamp, close)) in prices.iter().enumerate() {
+            let previous = index.checked_sub(1).map(|previous| prices[previous]);
+            let aligned = match (benchmark, previous) {
+                (Some((_, benchmark)), Some((previous_timestamp, previous_close))) => {
+                    let symbol_return = growth(previous_close, *close);
+                    let benchmark_return =
+                        growth_between(benchmark, previous_timestamp, *timestamp);
+                    match (symbol_return, benchmark_return) {
+                        (Some(y), Some(x)) => {
+                            regression.push(x - 1.0, y - 1.0);
+                            true
+                        }
+                        _ => false,
+                    }
+                }
+                _ => false,
+            };
+            if *timestamp < from {
+                continue;
+            }
+
+            let lookback_start = index
+                .checked_sub(lookback.return_bars)
+                .map(|start| prices[start]);
+            let own_growth =
+                lookback_start.and_then(|(_, start_close)| growth(start_close, *close));
+            let relative_to = |benchmark: Option<&Closes>| {
+                let (start_timestamp, _) = lookback_start?;
+                let benchmark_growth = growth_between(benchmark?, start_timestamp, *timestamp)?;
+                Some((own_growth? / benchmark_growth - 1.0) as f32)
+            };
+            let (beta, correlation) = if aligned {
+                regression.beta_and_correlation()
+            } else {
+                (None, None)
+            };
+            let percentile = sector_ranks
+                .and_then(|ranks| ranks.get(timestamp))
+                .zip(own_growth)
+                .and_then(|(returns, own_growth)| percentile_rank(returns, own_growth));
+
+            rows.push(RelativeFeatures {
+                stock_symbol: ticker.clone(),
+                event_unix_timestamp: *timestamp,
+                benchmark_etf: benchmark.map(|(etf, _)| etf.to_string()),
+                relative_strength_vs_sector: relative_to(sector_etf.map(|(_, closes)| closes)),
+                relative_strength_vs_market: relative_to(market),
+                beta_to_benchmark: beta.map(|beta| beta as f32),
+                correlation_to_benchmark: correlation.map(|correlation| correlation as f32),
+                sector_percentile_rank: percentile.map(|percentile| percentile as f32),
+            });
+        }
+    }
+    rows
+}
+
+/// Price ratio `to / from`, when both prices are positive.
+pub(crate) fn growth(from: f64, to: f64) -> Option<f64> {
+    (from > 0.0 && to > 0.0).then(|| to / from)
+}
+
+fn growth_between(prices: &Closes, from: i64, to: i64) -> Option<f64> {
+    growth(*prices.get(&from)?, *prices.get(&to)?)
+}
+
+/// Growth over the last `bars` bars at every bar.
+fn lookback_growth(prices: &Closes, bars: usize) -> Vec<(i64, f64)> {
+    let prices: Vec<(i64, f64)> = prices.iter().map(|(t, c)| (*t, *c)).collect();
+    (bars..prices.len())
+        .filter_map(|index| {
+            let (timestamp, close) = prices[index];
+            Some((timestamp, growth(prices[index - bars].1, close)?))
+        })
+        .collect()
+}
+
+/// Share of `sorted` below `value`, counting equal values besides `value`
+/// itself as half. `None` for a sector of one.
+pub(crate) fn percentile_rank(sorted: &[f64], value: f64) -> Option<f64> {
+    if sorted.len() < 2 {
+        return None;
+    }
+    let below = sorted.partition_point(|other| *other < value);
+    let equal = sorted[below..].partition_point(|other| *other <= value);
+    Some((below as f64 + equal.saturating_sub(1) as f64 / 2.0) / (sorted.len() - 1) as f64)
+}
+
+/// Running sums over the last `len` pairs of benchmark (`x`) and symbol (`y`)
+/// returns.
+struct RollingRegression {
+    len: usize,
+    pairs: VecDeque<(f64, f64)>,
+    sum_x: f64,
+    sum_y: f64,
+    sum_xx: f64,
+    sum_yy: f64,
+    sum_xy: f64,
+}
+
+impl RollingRegression {
+    fn new(len: usize) -> Self {
+        Self {
+            len,
+            pairs: VecDeque::with_capacity(len + 1),
+            sum_x: 0.0,
+            sum_y: 0.0,
+            sum_xx: 0.0,
+            sum_yy: 0.0,
+            sum_xy: 0.0,
+        }
+    }
+
+    fn push(&mut self, x: f64, y: f64) {
+        self.add(x, y, 1.0);
+        self.pairs.push_back((x, y));
+        if self.pairs.len() > self.len {
+            if let Some((x, y)) = self.pairs.pop_front() {
+                self.add(x, y, -1.0);
+            }
+        }
+    }
+
+    fn add(&mut self, x: f64, y: f64, sign: f64) {
+        self.sum_x += sign * x;
+        self.sum_y += sign * y;
+        self.sum_xx += sign * x * x;
+        self.sum_yy += sign * y * y;
+        self.sum_xy += sign * x * y;
+    }
+
+    /// Beta of `y` on `x` and their correlation, once the window is full.
+    fn beta_and_correlation(&self) -> (Option<f64>, Option<f64>) {
+        if self.pairs.len() < self.len {
+            return (None, None);
+        }
+        let n = self.len as f64;
+        let covariance = self.sum_xy / n - (self.sum_x / n) * (self.sum_y / n);
+        let variance_x = self.sum_xx / n - (self.sum_x / n).powi(2);
+        let variance_y = self.sum_yy / n - (self.sum_y / n).powi(2);
+
+        const EPSILON: f64 = 1e-12;
+        let beta = (variance_x > EPSILON).then(|| covariance / variance_x);
+        let correlation = (variance_x > EPSILON && variance_y > EPSILON)
+            .then(|| (covariance / (variance_x * variance_y).sqrt()).clamp(-1.0, 1.0));
+        (beta, correlation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(ticker: &str, asset_class: AssetClass, benchmark_etf: Option<&str>) -> Symbol {
+        Symbol {
+            ticker: ticker.to_string(),
+            name: None,
+            asset_class,
+            sector: Some("Energy".to_string()),
+            benchmark_etf: benchmark_etf.map(str::to_string),
+            exchange: None,
+            active: true,
+            first_bar_date: None,
+            last_bar_date: None,
+        }
+    }
+
+    #[test]
+    fn test_relative_features_against_sector_etf_and_market() {
+        // XLE alternates +1% and -1%, XOM moves twice as much, CVX and SPY are flat
+        let mut xle = vec![100.0];
+        for index in 0..30 {
+            let last = *xle.last().unwrap();
+            xle.push(if index % 2 == 0 {
+                last * 1.01
+            } else {
+                last * 0.99
+            });
+        }
+        let xom: Vec<f64> = xle.windows(2).fold(vec![50.0], |mut prices, pair| {
+            let last = *prices.last().unwrap();
+            prices.push(last * (1.0 + 2.0 * (pair[1] / pair[0] - 1.0)));
+            prices
+        });
+        let series = |prices: &[f64]| -> Closes {
+            prices
+                .iter()
+                .enumerate()
+                .map(|(index, close)| (index as i64, *close))
+                .collect()
+        };
+        let closes = HashMap::from([
+            ("XLE".to_string(), series(&xle)),
+            ("XOM".to_string(), series(&xom)),
+            ("CVX".to_string(), series(&[20.0; 31])),
+            ("SPY".to_string(), series(&[400.0; 31])),
+        ]);
+        let symbols = HashMap::from([
+            ("XLE".to_string(), symbol("XLE", AssetClass::Etf, None)),
+            (
+                "XOM".to_string(),
+                symbol("XOM", AssetClass::Stock, Some("XLE")),
+            ),
+            (
+                "CVX".to_string(),
+                symbol("CVX", AssetClass::Stock, Some("XLE")),
+            ),
+        ]);
+        let lookback = RelativeLookback {
+            return_bars: 4,
+            beta_bars: 10,
+        };
+
+        let from = closes.keys().map(|ticker| (ticker.clone(), 20)).collect();
+        let rows = relative_features(&closes, &symbols, lookback, &from);
+        let row = |ticker: &str, timestamp: i64| {
+            rows.iter()
+                .find(|row| row.stock_symbol == ticker && row.event_unix_timestamp == timestamp)
+                .unwrap()
+        };
+
+        let xom = row("XOM", 30);
+        assert_eq!(xom.benchmark_etf.as_deref(), Some("XLE"));
+        assert!((xom.beta_to_benchmark.unwrap() - 2.0).abs() < 0.01);
+        assert!((xom.correlation_to_benchmark.unwrap() - 1.0).abs() < 0.001);
+        let xom_growth = closes["XOM"][&30] / closes["XOM"][&26];
+        assert!(
+            (xom.relative_strength_vs_market.unwrap() as f64 - (xom_growth - 1.0)).abs() < 1e-4
+        );
+
+        // SPY is flat, so the beta of XLE against it is undefined
+        let xle = row("XLE", 30);
+        assert_eq!(xle.benchmark_etf.as_deref(), Some("SPY"));
+        assert_eq!(xle.beta_to_benchmark, None);
+        assert_eq!(xle.sector_percentile_rank, None);
+        assert_eq!(row("CVX", 30).relative_strength_vs_market, Some(0.0));
+        assert_eq!(
+            rows.iter().filter(|row| row.stock_symbol == "SPY").count(),
+            11
+        );
+
+        // XOM lost more than flat CVX over the lookback
+        assert_eq!(row("XOM", 25).sector_percentile_rank, Some(0.0));
+        assert_eq!(row("CVX", 25).sector_percentile_rank, Some(1.0));
+    }
+
+    #[test]
+    fn test_load_ranges_cover_sector_peers_and_benchmarks() {
+        let mut apple = symbol("AAPL", AssetClass::Stock, None);
+        apple.sector = None;
+        let reference = HashMap::from([
+            ("XLE".to_string(), symbol("XLE", AssetClass::Etf, None)),
+            (
+                "XOM".to_string(),
+                symbol("XOM", AssetClass::Stock, Some("XLE")),
+            ),
+            (
+                "CVX".to_string(),
+                symbol("CVX", AssetClass::Stock, Some("XLE")),
+            ),
+            ("AAPL".to_string(), apple),
+        ]);
+        let from = HashMap::from([
+            ("XOM".to_string(), Some(100)),
+            ("AAPL".to_string(), Some(50)),
+        ]);
+
+        let ranges = load_ranges(&from, &reference);
+
+        assert_eq!(
+            ranges,
+            HashMap::from([
+                ("XOM".to_string(), Some(100)),
+                ("CVX".to_string(), Some(100)),
+                ("XLE".to_string(), Some(100)),
+                ("SPY".to_string(), Some(50)),
+                ("AAPL".to_string(), Some(50)),
+            ])
+        );
+
+        // A symbol without stored features needs its peers' whole history
+        let from = HashMap::from([("CVX".to_string(), None), ("XOM".to_string(), Some(100))]);
+        assert_eq!(load_ranges(&from, &reference)["XOM"], None);
+    }
+}
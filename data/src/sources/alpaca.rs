@@ -0,0 +1,32 @@
+use std::collections::HashMap;
+
+use alpaca_api_client::market_data::stocks::{HistoricalBarsQuery, StockBar};
+use anyhow::{Context, Result};
+use database::Timeframe;
+
+use super::BarSource;
+
+/// Live historical bars from the Alpaca market data API. Needs the API keys in
+/// the environment.
+pub struct AlpacaBarSource;
+
+impl BarSource for AlpacaBarSource {
+    fn fetch_bars(
+        &self,
+        symbols: &[&str],
+        timeframe: Timeframe,
+        start: &str,
+        end: Option<&str>,
+    ) -> Result<HashMap<String, Vec<StockBar>>> {
+        let mut query =
+            HistoricalBarsQuery::new(symbols.to_vec(), timeframe.alpaca_timeframe()).start(start);
+        if let Some(end) = end {
+            query = query.end(end);
+        }
+
+        // Keeps the `ureq::Error` in the chain for `ScheduledBarSource` retries
+        query
+            .send()
+            .context("Failed to fetch historical stock data")
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+use alpaca_api_client::market_data::stocks::StockBar;
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use database::{BarProvenance, Timeframe};
+
+mod alpaca;
+pub use alpaca::*;
+mod replay;
+pub use replay::*;
+mod resampled;
+pub use resampled::*;
+mod scheduled;
+pub use scheduled::*;
+mod vendor_csv;
+pub use vendor_csv::*;
+
+/// Where ingestion gets its bars from.
+pub trait BarSource {
+    /// Bars of each symbol between `start` and `end` (inclusive, `YYYY-MM-DD` or
+    /// RFC 3339), oldest first. `end` defaults to the latest available bar.
+    fn fetch_bars(
+        &self,
+        symbols: &[&str],
+        timeframe: Timeframe,
+        start: &str,
+        end: Option<&str>,
+    ) -> Result<HashMap<String, Vec<StockBar>>>;
+
+    /// Recorded with every bar stored from this source.
+    fn provenance(&self) -> BarProvenance {
+        BarProvenance::Fetched
+    }
+}
+
+/// Range bounds are either RFC 3339 timestamps or whole days.
+fn parse_bound(bound: &str, end_of_day: bool) -> Result<DateTime<Utc>> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(bound) {
+        return Ok(datetime.with_timezone(&Utc));
+    }
+
+    let date = NaiveDate::parse_from_str(bound, "%Y-%m-%d")
+        .with_context(|| format!("Invalid date: {}", bound))?;
+    let datetime = if end_of_day {
+        date.and_hms_milli_opt(23, 59, 59, 999)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    };
+    Ok(datetime.expect("valid time of day").and_utc())
+}
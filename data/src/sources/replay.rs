@@ -0,0 +1,183 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use alpaca_api_client::market_data::stocks::StockBar;
+use anyhow::{anyhow, Context, Result};
+use chrono::DateTime;
+use database::Timeframe;
+use serde::Deserialize;
+
+use super::{parse_bound, BarSource};
+
+/// Replays recorded bar responses from disk instead of calling Alpaca, so the
+/// pipeline can run offline for tests, demos and reproducible rebuilds.
+///
+/// Recordings live in one directory per Alpaca bar size, e.g. `<root>/1Day/`:
+/// - `*.json`: a raw multi-symbol bars response, `{"bars": {"AAPL": [...]}}`
+/// - `*.csv`: one bar per line under a `symbol,t,o,h,l,c,v,n,vw` header
+///
+/// Bars from every file are merged per symbol, de-duplicated on `t` and sorted.
+pub struct ReplayBarSource {
+    root: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct RecordedResponse {
+    bars: HashMap<String, Vec<StockBar>>,
+}
+
+impl ReplayBarSource {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn recordings(&self, timeframe: Timeframe) -> Result<Vec<PathBuf>> {
+        let dir = self.root.join(timeframe.alpaca_timeframe().to_string());
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(&dir)
+            .with_context(|| format!("No recordings found in {}", dir.display()))?
+        {
+            let path = entry?.path();
+            if matches!(
+                path.extension().and_then(|extension| extension.to_str()),
+                Some("json" | "csv")
+            ) {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        Ok(paths)
+    }
+}
+
+impl BarSource for ReplayBarSource {
+    fn fetch_bars(
+        &self,
+        symbols: &[&str],
+        timeframe: Timeframe,
+        start: &str,
+        end: Option<&str>,
+    ) -> Result<HashMap<String, Vec<StockBar>>> {
+        let start = parse_bound(start, false)?.timestamp_millis();
+        let end = match end {
+            Some(end) => parse_bound(end, true)?.timestamp_millis(),
+            None => i64::MAX,
+        };
+
+        let mut recorded: HashMap<String, Vec<StockBar>> = HashMap::new();
+        for path in self.recordings(timeframe)? {
+            let bars = if path.extension().is_some_and(|extension| extension == "csv") {
+                read_csv(&path)?
+            } else {
+                read_json(&path)?
+            };
+            for (symbol, bars) in bars {
+                if symbols.contains(&symbol.as_str()) {
+                    recorded.entry(symbol).or_default().extend(bars);
+                }
+            }
+        }
+
+        let mut bars_map = HashMap::new();
+        for (symbol, bars) in recorded {
+            let mut timestamped = Vec::with_capacity(bars.len());
+            for bar in bars {
+                let timestamp = DateTime::parse_from_rfc3339(&bar.t)
+                    .with_context(|| format!("Invalid bar timestamp for {}: {}", symbol, bar.t))?
+                    .timestamp_millis();
+                if (start..=end).contains(&timestamp) {
+                    timestamped.push((timestamp, bar));
+                }
+            }
+            timestamped.sort_by_key(|(timestamp, _)| *timestamp);
+            timestamped.dedup_by_key(|(timestamp, _)| *timestamp);
+
+            bars_map.insert(
+                symbol,
+                timestamped.into_iter().map(|(_, bar)| bar).collect(),
+            );
+        }
+
+        Ok(bars_map)
+    }
+}
+
+fn read_json(path: &Path) -> Result<HashMap<String, Vec<StockBar>>> {
+    let file = File::open(path)?;
+    let response: RecordedResponse = serde_json::from_reader(file)
+        .with_context(|| format!("Invalid bars response in {}", path.display()))?;
+    Ok(response.bars)
+}
+
+fn read_csv(path: &Path) -> Result<HashMap<String, Vec<StockBar>>> {
+    let mut reader = csv::Reader::from_path(path)?;
+    let headers = reader.headers()?.clone();
+    let symbol_column = headers
+        .iter()
+        .position(|header| header == "symbol")
+        .ok_or_else(|| anyhow!("{} has no symbol column", path.display()))?;
+
+    let mut bars: HashMap<String, Vec<StockBar>> = HashMap::new();
+    for (index, record) in reader.records().enumerate() {
+        // Line 1 is the header
+        let line = index + 2;
+        let record = record.with_context(|| format!("{}:{}", path.display(), line))?;
+        let bar: StockBar = record
+            .deserialize(Some(&headers))
+            .with_context(|| format!("{}:{}: invalid bar", path.display(), line))?;
+        bars.entry(record[symbol_column].to_string())
+            .or_default()
+            .push(bar);
+    }
+    Ok(bars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_merges_recordings_within_range() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root
+            .path()
+            .join(Timeframe::Daily.alpaca_timeframe().to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("page_1.json"),
+            r#"{"bars": {"AAPL": [
+                {"t": "2024-01-03T05:00:00Z", "o": 2.0, "h": 3.0, "l": 1.0, "c": 2.5, "v": 10.0, "n": 1, "vw": 2.2},
+                {"t": "2024-01-02T05:00:00Z", "o": 1.0, "h": 2.0, "l": 0.5, "c": 1.5, "v": 10.0, "n": 1, "vw": 1.2}
+            ]}, "next_page_token": null}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("page_2.csv"),
+            "symbol,t,o,h,l,c,v,n,vw\n\
+             AAPL,2024-01-03T05:00:00Z,2.0,3.0,1.0,2.5,10.0,1,2.2\n\
+             AAPL,2024-01-04T05:00:00Z,3.0,4.0,2.0,3.5,10.0,1,3.2\n\
+             MSFT,2024-01-04T05:00:00Z,3.0,4.0,2.0,3.5,10.0,1,3.2\n",
+        )
+        .unwrap();
+
+        let source = ReplayBarSource::new(root.path());
+        let bars = source
+            .fetch_bars(
+                &["AAPL"],
+                Timeframe::Daily,
+                "2024-01-03",
+                Some("2024-01-04"),
+            )
+            .unwrap();
+
+        let timestamps: Vec<&str> = bars["AAPL"].iter().map(|bar| bar.t.as_str()).collect();
+        assert_eq!(
+            timestamps,
+            vec!["2024-01-03T05:00:00Z", "2024-01-04T05:00:00Z"]
+        );
+        assert!(!bars.contains_key("MSFT"));
+    }
+}
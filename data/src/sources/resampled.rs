@@ -0,0 +1,428 @@
+use std::{collections::HashMap, fmt};
+
+use alpaca_api_client::market_data::stocks::StockBar;
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use database::{AdjustmentMode, BarProvenance, SqliteDb, StockBarModel, StockBarsQuery, Timeframe};
+use futures_util::TryStreamExt;
+
+use super::{parse_bound, BarSource};
+use crate::calendar::{self, Session};
+
+/// Largest relative difference in any of open, high, low and close for a
+/// resampled bar to count as matching the fetched one.
+const PRICE_TOLERANCE: f32 = 0.005;
+/// Volume is looser: the consolidated daily and hourly bars include trades
+/// that are not attributed to any fifteen-minute bar.
+const VOLUME_TOLERANCE: f32 = 0.05;
+
+const SOURCE_COLUMNS: [&str; 9] = [
+    "event_unix_timestamp",
+    "open_price",
+    "high_price",
+    "low_price",
+    "close_price",
+    "volume",
+    "volume_weighted_price",
+    "adjustment_mode",
+    "provenance",
+];
+
+/// Builds coarser bars out of the stored fifteen-minute bars, so the hourly,
+/// daily, weekly and monthly tables can be filled without fetching the same
+/// trades from Alpaca again.
+///
+/// Fifteen-minute bars are grouped with the trading calendar:
+/// - hourly: clock hours within the extended session
+/// - daily: the regular session of each trading day
+/// - weekly and monthly: the regular sessions of each week or calendar month
+///
+/// The open and close come from the first and last bar, the high and low are
+/// the extremes, volume is summed and the VWAP is recomputed as
+/// `sum(vwap * volume) / sum(volume)`. Timestamps follow Alpaca: the start of
+/// the hour, or exchange midnight of the day, the week's Monday or the first
+/// of the month.
+///
+/// Only raw fifteen-minute bars are resampled. Adjustments are applied to the
+/// resampled bars by the ingest pipeline, which stores them with
+/// `BarProvenance::Resampled` so they can be told apart from fetched bars.
+/// Fetched bars of the same key are overwritten.
+pub struct ResampledBarSource {
+    timeframe: Timeframe,
+    bars: HashMap<String, Vec<ResampledBar>>,
+}
+
+/// Fifteen-minute bars aggregated into one bar of the target timeframe.
+#[derive(Debug)]
+struct ResampledBar {
+    timestamp: DateTime<Utc>,
+    open: f32,
+    high: f32,
+    low: f32,
+    close: f32,
+    volume: f64,
+    /// Sum of `vwap * volume` of the aggregated bars.
+    traded_value: f64,
+}
+
+impl ResampledBar {
+    fn new(timestamp: DateTime<Utc>, bar: &StockBarModel) -> Self {
+        let mut resampled = Self {
+            timestamp,
+            open: bar.open_price,
+            high: bar.high_price,
+            low: bar.low_price,
+            close: bar.close_price,
+            volume: 0.0,
+            traded_value: 0.0,
+        };
+        resampled.add_volume(bar);
+        resampled
+    }
+
+    fn push(&mut self, bar: &StockBarModel) {
+        self.high = self.high.max(bar.high_price);
+        self.low = self.low.min(bar.low_price);
+        self.close = bar.close_price;
+        self.add_volume(bar);
+    }
+
+    fn add_volume(&mut self, bar: &StockBarModel) {
+        // Bars imported before the VWAP column was required hold 0 and count
+        // at their close
+        let vwap = if bar.volume_weighted_price > 0.0 {
+            bar.volume_weighted_price
+        } else {
+            bar.close_price
+        };
+        self.volume += bar.volume as f64;
+        self.traded_value += vwap as f64 * bar.volume as f64;
+    }
+
+    fn vwap(&self) -> f32 {
+        if self.volume > 0.0 {
+            (self.traded_value / self.volume) as f32
+        } else {
+            self.close
+        }
+    }
+
+    fn to_stock_bar(&self) -> Result<StockBar> {
+        let bar = serde_json::from_value(serde_json::json!({
+            "t": self.timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+            "o": self.open,
+            "h": self.high,
+            "l": self.low,
+            "c": self.close,
+            "v": self.volume as f32,
+            "n": 0,
+            "vw": self.vwap(),
+        }))?;
+        Ok(bar)
+    }
+}
+
+impl ResampledBarSource {
+    /// Resamples every stored fifteen-minute bar of `symbols` into `timeframe`.
+    pub async fn load(db: &SqliteDb, timeframe: Timeframe, symbols: &[&str]) -> Result<Self> {
+        if timeframe == Timeframe::FifteenMin {
+            bail!("Fifteen-minute bars are the resampling source, pick a coarser timeframe");
+        }
+
+        let mut bars = HashMap::new();
+        for symbol in symbols {
+            let mut query = StockBarsQuery::new(Timeframe::FifteenMin)
+                .symbol(symbol)
+                .columns(&SOURCE_COLUMNS);
+            let mut stream = query.stream(db)?;
+
+            let mut resampled = Vec::new();
+            while let Some(bar) = stream.try_next().await? {
+                if bar.adjustment_mode != AdjustmentMode::Raw.as_str() {
+                    bail!(
+                        "{} has {} adjusted fifteen-minute bars, resample from raw bars and pass the adjustment instead",
+                        symbol,
+                        bar.adjustment_mode
+                    );
+                }
+                push_bar(&mut resampled, timeframe, &bar)?;
+            }
+            bars.insert(symbol.to_string(), resampled);
+        }
+
+        Ok(Self { timeframe, bars })
+    }
+
+    /// Day of the earliest resampled bar.
+    pub fn first_day(&self) -> Option<String> {
+        self.bars
+            .values()
+            .filter_map(|bars| bars.first())
+            .map(|bar| bar.timestamp)
+            .min()
+            .map(|timestamp| timestamp.format("%Y-%m-%d").to_string())
+    }
+
+    /// Compares the resampled bars with the raw fetched bars stored in the
+    /// timeframe's table, over the range both cover. Bars stored by an earlier
+    /// resampling are left out.
+    pub async fn consistency_report(&self, db: &SqliteDb) -> Result<Vec<ConsistencyReport>> {
+        let mut symbols: Vec<&String> = self.bars.keys().collect();
+        symbols.sort();
+
+        let mut reports = Vec::new();
+        for symbol in symbols {
+            let resampled = &self.bars[symbol];
+            let mut report = ConsistencyReport::new(symbol);
+            let (Some(first), Some(last)) = (resampled.first(), resampled.last()) else {
+                reports.push(report);
+                continue;
+            };
+
+            let mut unmatched: HashMap<i64, &ResampledBar> = resampled
+                .iter()
+                .map(|bar| (bar.timestamp.timestamp_millis(), bar))
+                .collect();
+            let fetched = StockBarsQuery::new(self.timeframe)
+                .symbol(symbol)
+                .start(first.timestamp)
+                .end(last.timestamp)
+                .columns(&SOURCE_COLUMNS)
+                .fetch_all(db)
+                .await?;
+            for fetched in fetched.iter().filter(|bar| {
+                bar.adjustment_mode == AdjustmentMode::Raw.as_str()
+                    && bar.provenance == BarProvenance::Fetched.as_str()
+            }) {
+                match unmatched.remove(&fetched.event_unix_timestamp) {
+                    Some(resampled) => report.compare(resampled, fetched)?,
+                    None => report.fetched_only += 1,
+                }
+            }
+            report.resampled_only = unmatched.len();
+            reports.push(report);
+        }
+
+        Ok(reports)
+    }
+}
+
+impl BarSource for ResampledBarSource {
+    fn fetch_bars(
+        &self,
+        symbols: &[&str],
+        timeframe: Timeframe,
+        start: &str,
+        end: Option<&str>,
+    ) -> Result<HashMap<String, Vec<StockBar>>> {
+        if timeframe != self.timeframe {
+            bail!(
+                "Bars were resampled for {}, not {}",
+                self.timeframe.table(),
+                timeframe.table()
+            );
+        }
+
+        let start = parse_bound(start, false)?;
+        let end = match end {
+            Some(end) => parse_bound(end, true)?,
+            None => DateTime::<Utc>::MAX_UTC,
+        };
+
+        let mut bars_map = HashMap::new();
+        for symbol in symbols {
+            let Some(bars) = self.bars.get(*symbol) else {
+                continue;
+            };
+            let in_range = bars
+                .iter()
+                .filter(|bar| (start..=end).contains(&bar.timestamp))
+                .map(ResampledBar::to_stock_bar)
+                .collect::<Result<Vec<_>>>()?;
+            bars_map.insert(symbol.to_string(), in_range);
+        }
+        Ok(bars_map)
+    }
+
+    fn provenance(&self) -> BarProvenance {
+        BarProvenance::Resampled
+    }
+}
+
+/// How resampled bars of a symbol line up with the bars fetched at the
+/// target timeframe.
+#[derive(Debug, Clone, Default)]
+pub struct ConsistencyReport {
+    pub stock_symbol: String,
+    /// Bars present both resampled and fetched.
+    pub compared: usize,
+    /// Compared bars outside `PRICE_TOLERANCE` or `VOLUME_TOLERANCE`.
+    pub mismatched: usize,
+    pub resampled_only: usize,
+    pub fetched_only: usize,
+    /// Largest relative difference in open, high, low or close.
+    pub max_price_diff: f32,
+    pub max_volume_diff: f32,
+    /// Timestamp of the compared bar with the largest price difference.
+    pub worst_bar: Option<DateTime<Utc>>,
+}
+
+impl ConsistencyReport {
+    fn new(symbol: &str) -> Self {
+        Self {
+            stock_symbol: symbol.to_string(),
+            ..Self::default()
+        }
+    }
+
+    fn compare(&mut self, resampled: &ResampledBar, fetched: &StockBarModel) -> Result<()> {
+        let price_diff = [
+            (resampled.open, fetched.open_price),
+            (resampled.high, fetched.high_price),
+            (resampled.low, fetched.low_price),
+            (resampled.close, fetched.close_price),
+        ]
+        .into_iter()
+        .map(|(resampled, fetched)| relative_diff(resampled, fetched))
+        .fold(0.0, f32::max);
+        let volume_diff = relative_diff(resampled.volume as f32, fetched.volume);
+
+        self.compared += 1;
+        if price_diff > PRICE_TOLERANCE || volume_diff > VOLUME_TOLERANCE {
+            self.mismatched += 1;
+        }
+        if price_diff > self.max_price_diff {
+            self.max_price_diff = price_diff;
+            self.worst_bar = Some(
+                DateTime::from_timestamp_millis(fetched.event_unix_timestamp).ok_or_else(|| {
+                    anyhow!("Invalid bar timestamp: {}", fetched.event_unix_timestamp)
+                })?,
+            );
+        }
+        self.max_volume_diff = self.max_volume_diff.max(volume_diff);
+        Ok(())
+    }
+}
+
+impl fmt::Display for ConsistencyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} compared, {} mismatched, {} resampled only, {} fetched only, max price diff {:.3}%, max volume diff {:.1}%",
+            self.stock_symbol,
+            self.compared,
+            self.mismatched,
+            self.resampled_only,
+            self.fetched_only,
+            self.max_price_diff * 100.0,
+            self.max_volume_diff * 100.0
+        )?;
+        if let Some(worst_bar) = self.worst_bar {
+            write!(f, " (at {})", worst_bar.to_rfc3339())?;
+        }
+        Ok(())
+    }
+}
+
+/// Adds a fifteen-minute bar to the last resampled bar, or starts a new one.
+/// Bars outside the sessions the timeframe covers are left out.
+fn push_bar(
+    resampled: &mut Vec<ResampledBar>,
+    timeframe: Timeframe,
+    bar: &StockBarModel,
+) -> Result<()> {
+    let Some(bucket) = bucket(timeframe, bar.event_unix_timestamp)? else {
+        return Ok(());
+    };
+    match resampled.last_mut() {
+        Some(last) if last.timestamp == bucket => last.push(bar),
+        _ => resampled.push(ResampledBar::new(bucket, bar)),
+    }
+    Ok(())
+}
+
+/// Timestamp of the resampled bar a fifteen-minute bar belongs to.
+fn bucket(timeframe: Timeframe, event_unix_timestamp: i64) -> Result<Option<DateTime<Utc>>> {
+    let datetime = DateTime::from_timestamp_millis(event_unix_timestamp)
+        .ok_or_else(|| anyhow!("Invalid bar timestamp: {}", event_unix_timestamp))?;
+    let day = calendar::exchange_date(datetime);
+
+    let session = match timeframe {
+        Timeframe::Hourly => Session::Extended,
+        _ => Session::Regular,
+    };
+    let in_session = calendar::session_hours(day, session)
+        .is_some_and(|(open, close)| (open..close).contains(&datetime));
+    if !in_session {
+        return Ok(None);
+    }
+
+    Ok(Some(match timeframe {
+        // Exchange time is a whole number of hours off UTC
+        Timeframe::Hourly => {
+            let hour = datetime.timestamp() - datetime.timestamp() % 3600;
+            DateTime::from_timestamp(hour, 0).expect("valid hour")
+        }
+        Timeframe::Daily => calendar::exchange_midnight(day),
+        Timeframe::Weekly => calendar::exchange_midnight(calendar::week_start(day)),
+        Timeframe::Monthly => calendar::exchange_midnight(calendar::month_start(day)),
+        Timeframe::FifteenMin => datetime,
+    }))
+}
+
+fn relative_diff(resampled: f32, fetched: f32) -> f32 {
+    if fetched == 0.0 {
+        return if resampled == 0.0 { 0.0 } else { f32::INFINITY };
+    }
+    ((resampled - fetched) / fetched).abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(t: &str, open: f32, high: f32, low: f32, close: f32, volume: f32) -> StockBarModel {
+        StockBarModel {
+            event_unix_timestamp: DateTime::parse_from_rfc3339(t).unwrap().timestamp_millis(),
+            open_price: open,
+            high_price: high,
+            low_price: low,
+            close_price: close,
+            volume,
+            volume_weighted_price: (high + low) / 2.0,
+            adjustment_mode: "raw".to_string(),
+            ..StockBarModel::default()
+        }
+    }
+
+    #[test]
+    fn test_daily_resampling_keeps_to_the_regular_session() {
+        // 2024-07-03 closed early at 13:00 exchange time (17:00 UTC)
+        let fifteen_minute_bars = [
+            bar("2024-07-03T13:15:00Z", 9.0, 9.0, 9.0, 9.0, 1000.0),
+            bar("2024-07-03T13:30:00Z", 10.0, 12.0, 9.5, 11.0, 100.0),
+            bar("2024-07-03T16:45:00Z", 11.0, 11.5, 10.5, 11.5, 300.0),
+            bar("2024-07-03T17:00:00Z", 13.0, 13.0, 13.0, 13.0, 1000.0),
+            bar("2024-07-05T13:30:00Z", 12.0, 12.0, 12.0, 12.0, 50.0),
+        ];
+        let mut resampled = Vec::new();
+        for fifteen_minute_bar in &fifteen_minute_bars {
+            push_bar(&mut resampled, Timeframe::Daily, fifteen_minute_bar).unwrap();
+        }
+        let source = ResampledBarSource {
+            timeframe: Timeframe::Daily,
+            bars: HashMap::from([("AAPL".to_string(), resampled)]),
+        };
+
+        let bars = source
+            .fetch_bars(&["AAPL"], Timeframe::Daily, "2024-07-01", None)
+            .unwrap();
+
+        let day = &bars["AAPL"][0];
+        assert_eq!(bars["AAPL"].len(), 2);
+        assert_eq!(day.t, "2024-07-03T04:00:00Z");
+        assert_eq!((day.o, day.h, day.l, day.c), (10.0, 12.0, 9.5, 11.5));
+        assert_eq!(day.v, 400.0);
+        assert!((day.vw - (100.0 * 10.75 + 300.0 * 11.0) / 400.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,454 @@
+use std::{
+    collections::HashMap,
+    io,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use alpaca_api_client::market_data::stocks::StockBar;
+use anyhow::Result;
+use chrono::{DateTime, SecondsFormat, Utc};
+use database::{BarProvenance, Timeframe};
+
+use super::{parse_bound, BarSource};
+use crate::RateLimitConfig;
+
+/// Longest wait between retries of a failed request.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Runs the fetches of a rate-limited network source as one request per symbol
+/// and date window, each small enough for a single page of the API. Requests
+/// are spread over up to `max_concurrency` threads, sent no faster than the
+/// configured requests per minute, and retried with exponential backoff when
+/// they fail with HTTP 429 or 5xx, or cannot reach the server.
+pub struct ScheduledBarSource<S> {
+    source: S,
+    limiter: RateLimiter,
+    max_concurrency: usize,
+    max_retries: u32,
+    backoff: Duration,
+}
+
+impl<S: BarSource + Sync> ScheduledBarSource<S> {
+    pub fn new(source: S, rate_limit: &RateLimitConfig) -> Self {
+        Self {
+            source,
+            limiter: RateLimiter::per_minute(
+                rate_limit.requests_per_minute,
+                rate_limit.max_concurrency,
+            ),
+            max_concurrency: rate_limit.max_concurrency.max(1),
+            max_retries: rate_limit.max_retries,
+            backoff: Duration::from_secs(1),
+        }
+    }
+
+    /// Wait before the first retry, doubled for every further one.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    fn fetch_with_retries(
+        &self,
+        request: &FetchRequest,
+        timeframe: Timeframe,
+        progress: &Progress,
+    ) -> Result<Vec<StockBar>> {
+        let mut attempt = 0;
+        loop {
+            self.limiter.acquire();
+            let result = self.source.fetch_bars(
+                &[request.symbol.as_str()],
+                timeframe,
+                &request.start,
+                request.end.as_deref(),
+            );
+            match result {
+                Ok(mut bars_map) => {
+                    return Ok(bars_map.remove(&request.symbol).unwrap_or_default())
+                }
+                Err(error) if attempt < self.max_retries && is_retryable(&error) => {
+                    let wait = self
+                        .backoff
+                        .saturating_mul(2u32.saturating_pow(attempt))
+                        .min(MAX_BACKOFF);
+                    progress.retry(request, &error, wait);
+                    thread::sleep(wait);
+                    attempt += 1;
+                }
+                Err(error) => {
+                    return Err(error.context(format!(
+                        "Failed to fetch {} from {}",
+                        request.symbol, request.start
+                    )))
+                }
+            }
+        }
+    }
+}
+
+impl<S: BarSource + Sync> BarSource for ScheduledBarSource<S> {
+    fn provenance(&self) -> BarProvenance {
+        self.source.provenance()
+    }
+
+    fn fetch_bars(
+        &self,
+        symbols: &[&str],
+        timeframe: Timeframe,
+        start: &str,
+        end: Option<&str>,
+    ) -> Result<HashMap<String, Vec<StockBar>>> {
+        let windows = windows(timeframe, start, end)?;
+        let requests: Vec<FetchRequest> = symbols
+            .iter()
+            .flat_map(|symbol| {
+                windows.iter().map(|(start, end)| FetchRequest {
+                    symbol: symbol.to_string(),
+                    start: start.clone(),
+                    end: end.clone(),
+                })
+            })
+            .collect();
+
+        let next = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        let fetched: Mutex<Vec<Option<Result<Vec<StockBar>>>>> =
+            Mutex::new(requests.iter().map(|_| None).collect());
+        let progress = Progress::new(requests.len());
+
+        thread::scope(|scope| {
+            for _ in 0..self.max_concurrency.min(requests.len()) {
+                scope.spawn(|| {
+                    // Stop taking new requests once one has failed for good
+                    while !failed.load(Ordering::Relaxed) {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(request) = requests.get(index) else {
+                            break;
+                        };
+
+                        let result = self.fetch_with_retries(request, timeframe, &progress);
+                        if result.is_err() {
+                            failed.store(true, Ordering::Relaxed);
+                        }
+                        fetched.lock().expect("fetch results lock")[index] = Some(result);
+                        progress.finish_request();
+                    }
+                });
+            }
+        });
+        progress.finish();
+
+        // Requests are ordered by symbol and then window, so bars stay oldest first
+        let mut bars_map: HashMap<String, Vec<StockBar>> = HashMap::new();
+        let fetched = fetched.into_inner().expect("fetch results lock");
+        for (request, result) in requests.iter().zip(fetched) {
+            let Some(bars) = result.transpose()? else {
+                continue;
+            };
+            if !bars.is_empty() {
+                bars_map
+                    .entry(request.symbol.clone())
+                    .or_default()
+                    .extend(bars);
+            }
+        }
+        Ok(bars_map)
+    }
+}
+
+struct FetchRequest {
+    symbol: String,
+    start: String,
+    end: Option<String>,
+}
+
+/// Calendar time of a request, keeping it under the API's page of 10,000 bars
+/// with extended-hours sessions. Coarser timeframes fit a whole range in one.
+fn window_length(timeframe: Timeframe) -> Option<chrono::Duration> {
+    match timeframe {
+        // About 63 sessions of 64 bars
+        Timeframe::FifteenMin => Some(chrono::Duration::days(90)),
+        // About 252 sessions of 16 bars
+        Timeframe::Hourly => Some(chrono::Duration::days(365)),
+        Timeframe::Daily | Timeframe::Weekly | Timeframe::Monthly => None,
+    }
+}
+
+/// Splits a range into consecutive, non-overlapping windows. The last window
+/// keeps the original end, so an open range still reaches the latest bar.
+fn windows(
+    timeframe: Timeframe,
+    start: &str,
+    end: Option<&str>,
+) -> Result<Vec<(String, Option<String>)>> {
+    let Some(length) = window_length(timeframe) else {
+        return Ok(vec![(start.to_string(), end.map(str::to_string))]);
+    };
+
+    let format = |datetime: DateTime<Utc>| datetime.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let until = match end {
+        Some(end) => parse_bound(end, true)?,
+        None => Utc::now(),
+    };
+    let mut from = parse_bound(start, false)?;
+    let mut windows = Vec::new();
+    loop {
+        let next = from + length;
+        if next > until {
+            windows.push((format(from), end.map(str::to_string)));
+            return Ok(windows);
+        }
+        windows.push((
+            format(from),
+            Some(format(next - chrono::Duration::seconds(1))),
+        ));
+        from = next;
+    }
+}
+
+/// Rate limiting, server errors and failures to reach the server or to read
+/// its response in time are worth retrying.
+fn is_retryable(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        if let Some(error) = cause.downcast_ref::<ureq::Error>() {
+            return match error {
+                ureq::Error::Status(code, _) => is_retryable_status(*code),
+                ureq::Error::Transport(transport) => is_retryable_transport(transport.kind()),
+            };
+        }
+        // Reading the response body fails with a plain I/O error
+        cause
+            .downcast_ref::<io::Error>()
+            .is_some_and(|error| is_retryable_io(error.kind()))
+    })
+}
+
+fn is_retryable_status(code: u16) -> bool {
+    code == 429 || (500..600).contains(&code)
+}
+
+fn is_retryable_transport(kind: ureq::ErrorKind) -> bool {
+    matches!(
+        kind,
+        ureq::ErrorKind::Dns
+            | ureq::ErrorKind::ConnectionFailed
+            | ureq::ErrorKind::ProxyConnect
+            | ureq::ErrorKind::Io
+    )
+}
+
+fn is_retryable_io(kind: io::ErrorKind) -> bool {
+    matches!(
+        kind,
+        io::ErrorKind::TimedOut
+            | io::ErrorKind::WouldBlock
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::UnexpectedEof
+    )
+}
+
+/// Token bucket refilled at a steady rate. It starts full, so the first `burst`
+/// requests go out at once.
+pub struct RateLimiter {
+    per_second: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    pub fn per_minute(requests: u32, burst: usize) -> Self {
+        let burst = burst.max(1) as f64;
+        Self {
+            per_second: f64::from(requests.max(1)) / 60.0,
+            burst,
+            bucket: Mutex::new(Bucket {
+                tokens: burst,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Blocks the calling thread until a request may be sent.
+    pub fn acquire(&self) {
+        while let Err(wait) = self.try_acquire(Instant::now()) {
+            thread::sleep(wait);
+        }
+    }
+
+    /// Takes a token, or returns how long until one is available.
+    fn try_acquire(&self, now: Instant) -> Result<(), Duration> {
+        let mut bucket = self.bucket.lock().expect("rate limiter lock");
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * self.per_second).min(self.burst);
+        bucket.refilled_at = bucket.refilled_at.max(now);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / self.per_second,
+            ))
+        }
+    }
+}
+
+/// Single progress line on stderr, which keeps it out of redirected output.
+struct Progress {
+    total: usize,
+    started: Instant,
+    counts: Mutex<(usize, usize)>,
+}
+
+impl Progress {
+    fn new(total: usize) -> Self {
+        Self {
+            total,
+            started: Instant::now(),
+            counts: Mutex::new((0, 0)),
+        }
+    }
+
+    fn finish_request(&self) {
+        let mut counts = self.counts.lock().expect("progress lock");
+        counts.0 += 1;
+        eprint!(
+            "\rFetched {}/{} requests, {} retries, {}s elapsed",
+            counts.0,
+            self.total,
+            counts.1,
+            self.started.elapsed().as_secs()
+        );
+    }
+
+    fn retry(&self, request: &FetchRequest, error: &anyhow::Error, wait: Duration) {
+        let mut counts = self.counts.lock().expect("progress lock");
+        counts.1 += 1;
+        eprintln!(
+            "\nRetrying {} from {} in {}s: {:#}",
+            request.symbol,
+            request.start,
+            wait.as_secs_f64(),
+            error
+        );
+    }
+
+    fn finish(&self) {
+        if self.total > 0 {
+            eprintln!();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use anyhow::anyhow;
+
+    use super::*;
+
+    /// Fails the first request of every window with a 429, then returns one
+    /// bar stamped at the window start.
+    struct FlakySource {
+        failed: Mutex<HashSet<String>>,
+    }
+
+    impl BarSource for FlakySource {
+        fn fetch_bars(
+            &self,
+            symbols: &[&str],
+            _timeframe: Timeframe,
+            start: &str,
+            _end: Option<&str>,
+        ) -> Result<HashMap<String, Vec<StockBar>>> {
+            let key = format!("{} {}", symbols[0], start);
+            if self.failed.lock().unwrap().insert(key) {
+                return Err(rate_limited().context("Failed to fetch historical stock data"));
+            }
+
+            let bar = serde_json::from_value(serde_json::json!({
+                "t": start, "o": 1.0, "h": 1.0, "l": 1.0, "c": 1.0, "v": 100.0, "n": 1, "vw": 1.0,
+            }))?;
+            Ok(HashMap::from([(symbols[0].to_string(), vec![bar])]))
+        }
+    }
+
+    fn rate_limited() -> anyhow::Error {
+        let response = ureq::Response::new(429, "Too Many Requests", "").unwrap();
+        ureq::Error::Status(429, response).into()
+    }
+
+    #[test]
+    fn test_is_retryable_matches_client_errors() {
+        let forbidden = ureq::Response::new(403, "Forbidden", "").unwrap();
+        let unavailable = ureq::Response::new(503, "Service Unavailable", "").unwrap();
+        let reset = io::Error::from(io::ErrorKind::ConnectionReset);
+
+        assert!(is_retryable(&rate_limited()));
+        assert!(is_retryable(&ureq::Error::Status(503, unavailable).into()));
+        assert!(!is_retryable(&ureq::Error::Status(403, forbidden).into()));
+        assert!(is_retryable(&anyhow::Error::from(reset).context("fetch")));
+        assert!(is_retryable_transport(ureq::ErrorKind::ConnectionFailed));
+        assert!(is_retryable_transport(ureq::ErrorKind::Dns));
+        assert!(!is_retryable_transport(ureq::ErrorKind::InvalidUrl));
+        assert!(!is_retryable_transport(ureq::ErrorKind::BadHeader));
+        assert!(!is_retryable_io(io::ErrorKind::PermissionDenied));
+        assert!(!is_retryable(&anyhow!("status code 429")));
+    }
+
+    #[test]
+    fn test_scheduled_fetch_splits_windows_and_retries() {
+        let rate_limit = RateLimitConfig {
+            requests_per_minute: 60_000,
+            max_retries: 1,
+            max_concurrency: 3,
+        };
+        let source = ScheduledBarSource::new(
+            FlakySource {
+                failed: Mutex::new(HashSet::new()),
+            },
+            &rate_limit,
+        )
+        .backoff(Duration::from_millis(1));
+
+        let bars_map = source
+            .fetch_bars(
+                &["AAPL", "MSFT"],
+                Timeframe::FifteenMin,
+                "2024-01-01",
+                Some("2024-06-30"),
+            )
+            .unwrap();
+
+        let starts: Vec<&str> = bars_map["AAPL"].iter().map(|bar| bar.t.as_str()).collect();
+        assert_eq!(
+            starts,
+            [
+                "2024-01-01T00:00:00Z",
+                "2024-03-31T00:00:00Z",
+                "2024-06-29T00:00:00Z"
+            ]
+        );
+        assert_eq!(bars_map["MSFT"].len(), 3);
+
+        let limiter = RateLimiter::per_minute(60, 2);
+        let now = Instant::now();
+        assert!(limiter.try_acquire(now).is_ok());
+        assert!(limiter.try_acquire(now).is_ok());
+        assert!(limiter.try_acquire(now).is_err());
+        assert!(limiter.try_acquire(now + Duration::from_secs(1)).is_ok());
+    }
+}
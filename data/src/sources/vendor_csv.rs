@@ -0,0 +1,403 @@
+use std::{collections::HashMap, fmt, path::Path};
+
+use alpaca_api_client::market_data::stocks::StockBar;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use chrono_tz::Tz;
+use csv::StringRecord;
+use database::{BarProvenance, Timeframe};
+
+use super::BarSource;
+
+/// How the columns of a vendor OHLCV export map onto a bar.
+#[derive(Debug, Clone)]
+pub struct CsvMapping {
+    /// Column holding the symbol. Files without one need `symbol`.
+    pub symbol_column: Option<String>,
+    /// Symbol of every row, for single-symbol files.
+    pub symbol: Option<String>,
+    pub timestamp: String,
+    pub open: String,
+    pub high: String,
+    pub low: String,
+    pub close: String,
+    pub volume: String,
+    /// Required, since a bar without its VWAP would be stored as priced at 0.
+    pub vwap: String,
+    /// `chrono` format of the timestamp column, `unix` or `unix_ms`. RFC 3339
+    /// when unset. Formats without a time of day are read as midnight.
+    pub timestamp_format: Option<String>,
+    /// Zone of timestamps that carry no offset.
+    pub timezone: Tz,
+}
+
+impl Default for CsvMapping {
+    fn default() -> Self {
+        Self {
+            symbol_column: None,
+            symbol: None,
+            timestamp: "timestamp".to_string(),
+            open: "open".to_string(),
+            high: "high".to_string(),
+            low: "low".to_string(),
+            close: "close".to_string(),
+            volume: "volume".to_string(),
+            vwap: "vwap".to_string(),
+            timestamp_format: None,
+            timezone: Tz::UTC,
+        }
+    }
+}
+
+impl CsvMapping {
+    /// Points a bar field (`timestamp`, `open`, `high`, `low`, `close`,
+    /// `volume` or `vwap`) at a CSV column.
+    pub fn map(&mut self, field: &str, column: &str) -> Result<()> {
+        let column = column.to_string();
+        match field {
+            "timestamp" => self.timestamp = column,
+            "open" => self.open = column,
+            "high" => self.high = column,
+            "low" => self.low = column,
+            "close" => self.close = column,
+            "volume" => self.volume = column,
+            "vwap" => self.vwap = column,
+            _ => bail!("Unknown bar field: {}", field),
+        }
+        Ok(())
+    }
+}
+
+/// A CSV line that could not be turned into a bar.
+#[derive(Debug, Clone)]
+pub struct CsvLineError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for CsvLineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Bars parsed from a vendor CSV export, served like any other source so they
+/// go through the same feature and label pipeline as Alpaca bars.
+pub struct VendorCsvSource {
+    bars: HashMap<String, Vec<CsvBar>>,
+}
+
+/// A validated CSV row.
+struct CsvBar {
+    timestamp: DateTime<Utc>,
+    open: f32,
+    high: f32,
+    low: f32,
+    close: f32,
+    volume: f32,
+    vwap: f32,
+}
+
+impl CsvBar {
+    /// Builds the Alpaca bar representation so vendor rows and API bars share
+    /// the rest of the pipeline.
+    fn to_stock_bar(&self) -> Result<StockBar> {
+        let bar = serde_json::from_value(serde_json::json!({
+            "t": self.timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+            "o": self.open,
+            "h": self.high,
+            "l": self.low,
+            "c": self.close,
+            "v": self.volume,
+            "n": 0,
+            "vw": self.vwap,
+        }))?;
+        Ok(bar)
+    }
+}
+
+impl VendorCsvSource {
+    /// Parses every line of `path`. Invalid lines are left out and returned
+    /// alongside the source.
+    pub fn from_path(path: &Path, mapping: &CsvMapping) -> Result<(Self, Vec<CsvLineError>)> {
+        if mapping.symbol_column.is_none() && mapping.symbol.is_none() {
+            bail!("Either a symbol column or a symbol is required");
+        }
+
+        let mut reader = csv::Reader::from_path(path)?;
+        let columns = Columns::new(reader.headers()?, mapping)?;
+
+        let mut bars: HashMap<String, Vec<CsvBar>> = HashMap::new();
+        let mut errors = Vec::new();
+        for (index, record) in reader.records().enumerate() {
+            // Line 1 is the header
+            let line = index + 2;
+            let parsed = record
+                .map_err(|e| anyhow!(e))
+                .and_then(|record| columns.parse(&record, mapping));
+            match parsed {
+                Ok((symbol, bar)) => bars.entry(symbol).or_default().push(bar),
+                Err(e) => errors.push(CsvLineError {
+                    line,
+                    message: e.to_string(),
+                }),
+            }
+        }
+
+        for symbol_bars in bars.values_mut() {
+            symbol_bars.sort_by_key(|bar| bar.timestamp);
+            symbol_bars.dedup_by_key(|bar| bar.timestamp);
+        }
+
+        Ok((Self { bars }, errors))
+    }
+
+    pub fn symbols(&self) -> Vec<&str> {
+        let mut symbols: Vec<&str> = self.bars.keys().map(String::as_str).collect();
+        symbols.sort();
+        symbols
+    }
+
+    /// Day of the oldest bar in the file, `YYYY-MM-DD`.
+    pub fn first_day(&self) -> Option<String> {
+        let oldest = self
+            .bars
+            .values()
+            .filter_map(|bars| bars.first())
+            .map(|bar| bar.timestamp)
+            .min()?;
+        Some(oldest.format("%Y-%m-%d").to_string())
+    }
+}
+
+impl BarSource for VendorCsvSource {
+    fn fetch_bars(
+        &self,
+        symbols: &[&str],
+        _timeframe: Timeframe,
+        start: &str,
+        end: Option<&str>,
+    ) -> Result<HashMap<String, Vec<StockBar>>> {
+        let start = parse_day(start)?;
+        let end = match end {
+            Some(end) => parse_day(end)? + chrono::Duration::days(1),
+            None => DateTime::<Utc>::MAX_UTC,
+        };
+
+        let mut bars_map = HashMap::new();
+        for symbol in symbols {
+            let Some(bars) = self.bars.get(*symbol) else {
+                continue;
+            };
+            let in_range = bars
+                .iter()
+                .filter(|bar| bar.timestamp >= start && bar.timestamp < end)
+                .map(CsvBar::to_stock_bar)
+                .collect::<Result<Vec<_>>>()?;
+            bars_map.insert(symbol.to_string(), in_range);
+        }
+        Ok(bars_map)
+    }
+
+    fn provenance(&self) -> BarProvenance {
+        BarProvenance::Imported
+    }
+}
+
+/// Indices of the mapped columns in the header.
+struct Columns {
+    symbol: Option<usize>,
+    timestamp: usize,
+    open: usize,
+    high: usize,
+    low: usize,
+    close: usize,
+    volume: usize,
+    vwap: usize,
+}
+
+impl Columns {
+    fn new(headers: &StringRecord, mapping: &CsvMapping) -> Result<Self> {
+        let find = |column: &str| {
+            headers
+                .iter()
+                .position(|header| header.trim() == column)
+                .ok_or_else(|| anyhow!("Column {} not found in header", column))
+        };
+
+        Ok(Self {
+            symbol: mapping.symbol_column.as_deref().map(find).transpose()?,
+            timestamp: find(&mapping.timestamp)?,
+            open: find(&mapping.open)?,
+            high: find(&mapping.high)?,
+            low: find(&mapping.low)?,
+            close: find(&mapping.close)?,
+            volume: find(&mapping.volume)?,
+            vwap: find(&mapping.vwap)
+                .context("Every bar needs a VWAP, map its column with --mapping vwap=<column>")?,
+        })
+    }
+
+    fn parse(&self, record: &StringRecord, mapping: &CsvMapping) -> Result<(String, CsvBar)> {
+        let field = |index: usize| {
+            record
+                .get(index)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| anyhow!("missing value in column {}", index + 1))
+        };
+        let number = |index: usize, name: &str| -> Result<f32> {
+            let value = field(index)?;
+            value
+                .parse::<f32>()
+                .ok()
+                .filter(|number| number.is_finite())
+                .ok_or_else(|| anyhow!("invalid {} {:?}", name, value))
+        };
+
+        let symbol = match (self.symbol, &mapping.symbol) {
+            (Some(index), _) => field(index)?.to_uppercase(),
+            (None, Some(symbol)) => symbol.to_uppercase(),
+            (None, None) => bail!("no symbol"),
+        };
+        let timestamp = parse_timestamp(field(self.timestamp)?, mapping)?;
+        let open = number(self.open, "open")?;
+        let high = number(self.high, "high")?;
+        let low = number(self.low, "low")?;
+        let close = number(self.close, "close")?;
+        let volume = number(self.volume, "volume")?;
+        let vwap = number(self.vwap, "vwap")?;
+
+        if low <= 0.0 {
+            bail!("prices must be positive, low is {}", low);
+        }
+        if vwap <= 0.0 {
+            bail!("prices must be positive, vwap is {}", vwap);
+        }
+        if high < open.max(close).max(low) || low > open.min(close) {
+            bail!(
+                "inconsistent OHLC: open {} high {} low {} close {}",
+                open,
+                high,
+                low,
+                close
+            );
+        }
+        if volume < 0.0 {
+            bail!("negative volume {}", volume);
+        }
+
+        let bar = CsvBar {
+            timestamp,
+            open,
+            high,
+            low,
+            close,
+            volume,
+            vwap,
+        };
+        Ok((symbol, bar))
+    }
+}
+
+fn parse_timestamp(value: &str, mapping: &CsvMapping) -> Result<DateTime<Utc>> {
+    let local = |naive: NaiveDateTime| {
+        mapping
+            .timezone
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|datetime| datetime.with_timezone(&Utc))
+            .ok_or_else(|| anyhow!("{} does not exist in {}", value, mapping.timezone))
+    };
+
+    match mapping.timestamp_format.as_deref() {
+        None => Ok(DateTime::parse_from_rfc3339(value)
+            .map_err(|_| anyhow!("invalid RFC 3339 timestamp {:?}", value))?
+            .with_timezone(&Utc)),
+        Some("unix") => value
+            .parse::<i64>()
+            .ok()
+            .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+            .ok_or_else(|| anyhow!("invalid unix timestamp {:?}", value)),
+        Some("unix_ms") => value
+            .parse::<i64>()
+            .ok()
+            .and_then(DateTime::from_timestamp_millis)
+            .ok_or_else(|| anyhow!("invalid unix millisecond timestamp {:?}", value)),
+        Some(format) => {
+            if let Ok(datetime) = DateTime::parse_from_str(value, format) {
+                return Ok(datetime.with_timezone(&Utc));
+            }
+            if let Ok(naive) = NaiveDateTime::parse_from_str(value, format) {
+                return local(naive);
+            }
+            let date = NaiveDate::parse_from_str(value, format)
+                .map_err(|_| anyhow!("timestamp {:?} does not match {:?}", value, format))?;
+            local(date.and_hms_opt(0, 0, 0).expect("valid midnight"))
+        }
+    }
+}
+
+fn parse_day(day: &str) -> Result<DateTime<Utc>> {
+    let date =
+        NaiveDate::parse_from_str(day, "%Y-%m-%d").map_err(|_| anyhow!("Invalid date: {}", day))?;
+    Ok(date.and_hms_opt(0, 0, 0).expect("valid midnight").and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vendor_csv_reports_invalid_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vendor_bars.csv");
+        std::fs::write(
+            &path,
+            "Ticker,Date,Open,High,Low,Close,Volume,VWAP\n\
+             aapl,01/03/2024,2.0,3.0,1.0,2.5,100,2.25\n\
+             AAPL,01/02/2024,1.0,2.0,0.5,1.5,100,1.25\n\
+             AAPL,01/04/2024,3.0,2.0,1.0,2.5,100,2.25\n\
+             AAPL,2024-01-05,3.0,4.0,2.0,3.5,100,3.25\n",
+        )
+        .unwrap();
+
+        let mut mapping = CsvMapping {
+            symbol_column: Some("Ticker".to_string()),
+            timestamp_format: Some("%m/%d/%Y".to_string()),
+            timezone: chrono_tz::America::New_York,
+            ..CsvMapping::default()
+        };
+        for (field, column) in [
+            ("timestamp", "Date"),
+            ("open", "Open"),
+            ("high", "High"),
+            ("low", "Low"),
+            ("close", "Close"),
+            ("volume", "Volume"),
+        ] {
+            mapping.map(field, column).unwrap();
+        }
+        let missing_vwap = VendorCsvSource::from_path(&path, &mapping)
+            .err()
+            .unwrap()
+            .to_string();
+        mapping.map("vwap", "VWAP").unwrap();
+
+        let (source, errors) = VendorCsvSource::from_path(&path, &mapping).unwrap();
+
+        assert!(missing_vwap.contains("--mapping vwap=<column>"));
+        let lines: Vec<usize> = errors.iter().map(|error| error.line).collect();
+        assert_eq!(lines, vec![4, 5]);
+        assert_eq!(source.first_day().as_deref(), Some("2024-01-02"));
+
+        let bars = source
+            .fetch_bars(&["AAPL"], Timeframe::Daily, "2024-01-01", None)
+            .unwrap();
+        let timestamps: Vec<&str> = bars["AAPL"].iter().map(|bar| bar.t.as_str()).collect();
+        assert_eq!(
+            timestamps,
+            vec!["2024-01-02T05:00:00Z", "2024-01-03T05:00:00Z"]
+        );
+    }
+}
@@ -0,0 +1,46 @@
+//! Fixtures shared by the unit tests.
+
+use std::collections::HashMap;
+
+use alpaca_api_client::market_data::stocks::StockBar;
+use anyhow::Result;
+use database::Timeframe;
+
+use crate::sources::BarSource;
+
+/// A bar at `t` (RFC 3339) with every price at `close`.
+fn bar(t: &str, close: f32) -> StockBar {
+    serde_json::from_value(serde_json::json!({
+        "t": t, "o": close, "h": close, "l": close, "c": close, "v": 100.0, "n": 1, "vw": close,
+    }))
+    .unwrap()
+}
+
+/// Returns the same bars, as `(t, close)`, for every symbol within the
+/// requested days.
+pub(crate) struct FixedBarSource(pub Vec<(&'static str, f32)>);
+
+impl BarSource for FixedBarSource {
+    fn fetch_bars(
+        &self,
+        symbols: &[&str],
+        _timeframe: Timeframe,
+        start: &str,
+        end: Option<&str>,
+    ) -> Result<HashMap<String, Vec<StockBar>>> {
+        let in_range =
+            |t: &str| t[..10] >= start[..10] && !end.is_some_and(|end| t[..10] > end[..10]);
+        Ok(symbols
+            .iter()
+            .map(|symbol| {
+                let bars = self
+                    .0
+                    .iter()
+                    .filter(|(t, _)| in_range(t))
+                    .map(|(t, close)| bar(t, *close))
+                    .collect();
+                (symbol.to_string(), bars)
+            })
+            .collect())
+    }
+}
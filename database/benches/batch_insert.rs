@@ -0,0 +1,118 @@
+//! Compares row-by-row upserts against the transactional multi-row batch upsert
+//! that ingestion uses, for a synthetic 2016-to-today daily history across the
+//! watchlist. The batch is timed a second time on the same rows, where every
+//! row is left unchanged, as when a catch-up re-fetches bars already stored.
+//!
+//! Run with `cargo bench -p database --bench batch_insert`. The row-by-row path
+//! is only timed on a handful of symbols since it commits every row.
+
+use std::time::Instant;
+
+use chrono::{Datelike, NaiveDate, Utc, Weekday};
+use database::{BarRepository, SqliteDb, StockBarModelEntry, Timeframe};
+
+const WATCHLIST_SIZE: usize = 470;
+const ROW_BY_ROW_SYMBOLS: usize = 5;
+
+#[tokio::main]
+async fn main() {
+    let trading_days = trading_days_since(NaiveDate::from_ymd_opt(2016, 1, 1).unwrap());
+    println!(
+        "{} symbols x {} daily bars = {} rows",
+        WATCHLIST_SIZE,
+        trading_days.len(),
+        WATCHLIST_SIZE * trading_days.len()
+    );
+
+    let spec = Timeframe::Daily.feature_spec();
+
+    let db = fresh_database("row_by_row").await;
+    let started = Instant::now();
+    let mut rows = 0;
+    for symbol_index in 0..ROW_BY_ROW_SYMBOLS {
+        for entry in symbol_entries(symbol_index, &trading_days) {
+            db.upsert_stock_bar(Timeframe::Daily, &spec, &entry)
+                .await
+                .unwrap();
+            rows += 1;
+        }
+    }
+    report("row by row", rows, started);
+
+    let db = fresh_database("batched").await;
+    for label in ["batched", "unchanged"] {
+        let started = Instant::now();
+        let mut rows = 0;
+        for symbol_index in 0..WATCHLIST_SIZE {
+            let entries = symbol_entries(symbol_index, &trading_days);
+            db.upsert_batch_of_stock_bars(Timeframe::Daily, &spec, &entries)
+                .await
+                .unwrap();
+            rows += entries.len();
+        }
+        report(label, rows, started);
+    }
+}
+
+async fn fresh_database(name: &str) -> SqliteDb {
+    let path = std::env::temp_dir().join(format!("stock_bars_bench_{}.db", name));
+    let uri = format!("sqlite://{}", path.display());
+    let _ = std::fs::remove_file(&path);
+
+    SqliteDb::create_new(&uri).await.unwrap()
+}
+
+fn report(label: &str, rows: usize, started: Instant) {
+    let elapsed = started.elapsed().as_secs_f64();
+    println!(
+        "{:>10}: {} rows in {:.2}s ({:.0} rows/s)",
+        label,
+        rows,
+        elapsed,
+        rows as f64 / elapsed
+    );
+}
+
+fn trading_days_since(start: NaiveDate) -> Vec<NaiveDate> {
+    let today = Utc::now().date_naive();
+    start
+        .iter_days()
+        .take_while(|day| *day <= today)
+        .filter(|day| !matches!(day.weekday(), Weekday::Sat | Weekday::Sun))
+        .collect()
+}
+
+fn symbol_entries(symbol_index: usize, trading_days: &[NaiveDate]) -> Vec<StockBarModelEntry> {
+    let stock_symbol = format!("SYM{}", symbol_index);
+    trading_days
+        .iter()
+        .enumerate()
+        .map(|(index, day)| {
+            let price = 100.0 + (index % 50) as f32;
+            let event = day.and_hms_opt(5, 0, 0).unwrap().and_utc();
+            let next_event = event + chrono::Duration::days(1);
+            StockBarModelEntry {
+                event_datetime: event.format("%Y-%m-%d %H:%M:%S").to_string(),
+                event_unix_timestamp: event.timestamp_millis(),
+                open_price: price,
+                close_price: price + 0.5,
+                high_price: price + 1.0,
+                low_price: price - 1.0,
+                volume: 1_000_000.0,
+                volume_weighted_price: price + 0.25,
+                stock_symbol: stock_symbol.clone(),
+                timeframe: "1Day".to_string(),
+                bar_trend: "Bullish".to_string(),
+                buy_or_sell: 1,
+                next_period_price: price + 1.5,
+                next_period_trend: "Bullish".to_string(),
+                next_period_unix_timestamp: next_event.timestamp_millis(),
+                next_period_event_datetime: next_event.format("%Y-%m-%d %H:%M:%S").to_string(),
+                previous_period_trend: "Bullish".to_string(),
+                adjustment_mode: "raw".to_string(),
+                provenance: "fetched".to_string(),
+                features: vec![Some(price); Timeframe::Daily.feature_spec().len()],
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,36 @@
+-- Add migration script here
+DELETE FROM monthly_stock_bars
+WHERE id NOT IN (
+    SELECT MAX(id) FROM monthly_stock_bars
+    GROUP BY stock_symbol, timeframe, event_unix_timestamp
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS monthly_stock_bars_symbol_timeframe_timestamp
+ON monthly_stock_bars (stock_symbol, timeframe, event_unix_timestamp);
+
+DELETE FROM daily_stock_bars
+WHERE id NOT IN (
+    SELECT MAX(id) FROM daily_stock_bars
+    GROUP BY stock_symbol, timeframe, event_unix_timestamp
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS daily_stock_bars_symbol_timeframe_timestamp
+ON daily_stock_bars (stock_symbol, timeframe, event_unix_timestamp);
+
+DELETE FROM hourly_stock_bars
+WHERE id NOT IN (
+    SELECT MAX(id) FROM hourly_stock_bars
+    GROUP BY stock_symbol, timeframe, event_unix_timestamp
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS hourly_stock_bars_symbol_timeframe_timestamp
+ON hourly_stock_bars (stock_symbol, timeframe, event_unix_timestamp);
+
+DELETE FROM fifteen_minute_stock_bars
+WHERE id NOT IN (
+    SELECT MAX(id) FROM fifteen_minute_stock_bars
+    GROUP BY stock_symbol, timeframe, event_unix_timestamp
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS fifteen_minute_stock_bars_symbol_timeframe_timestamp
+ON fifteen_minute_stock_bars (stock_symbol, timeframe, event_unix_timestamp);
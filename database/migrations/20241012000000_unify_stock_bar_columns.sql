@@ -0,0 +1,49 @@
+-- Add migration script here
+-- Every bar table shares the daily table's core columns
+ALTER TABLE monthly_stock_bars RENAME COLUMN next_frame_price TO next_period_price;
+ALTER TABLE monthly_stock_bars RENAME COLUMN next_frame_trend TO next_period_trend;
+ALTER TABLE monthly_stock_bars RENAME COLUMN next_frame_unix_timestamp TO next_period_unix_timestamp;
+ALTER TABLE monthly_stock_bars RENAME COLUMN next_frame_event_datetime TO next_period_event_datetime;
+ALTER TABLE monthly_stock_bars RENAME COLUMN ten_week_moving_avg TO ten_week_sma;
+
+ALTER TABLE hourly_stock_bars RENAME COLUMN next_frame_price TO next_period_price;
+ALTER TABLE hourly_stock_bars RENAME COLUMN next_frame_trend TO next_period_trend;
+ALTER TABLE hourly_stock_bars RENAME COLUMN next_frame_unix_timestamp TO next_period_unix_timestamp;
+ALTER TABLE hourly_stock_bars RENAME COLUMN next_frame_event_datetime TO next_period_event_datetime;
+
+ALTER TABLE fifteen_minute_stock_bars RENAME COLUMN next_frame_price TO next_period_price;
+ALTER TABLE fifteen_minute_stock_bars RENAME COLUMN next_frame_trend TO next_period_trend;
+ALTER TABLE fifteen_minute_stock_bars RENAME COLUMN next_frame_unix_timestamp TO next_period_unix_timestamp;
+ALTER TABLE fifteen_minute_stock_bars RENAME COLUMN next_frame_event_datetime TO next_period_event_datetime;
+
+-- previous_period_trend is back-filled from the previous bar, the first bar defaults to Bullish
+ALTER TABLE monthly_stock_bars ADD COLUMN previous_period_trend TEXT NOT NULL DEFAULT 'Bullish';
+UPDATE monthly_stock_bars
+SET previous_period_trend = previous.trend
+FROM (
+    SELECT id, LAG(bar_trend, 1, 'Bullish') OVER (PARTITION BY stock_symbol ORDER BY event_unix_timestamp) AS trend
+    FROM monthly_stock_bars
+) AS previous
+WHERE monthly_stock_bars.id = previous.id;
+
+ALTER TABLE hourly_stock_bars ADD COLUMN previous_period_trend TEXT NOT NULL DEFAULT 'Bullish';
+UPDATE hourly_stock_bars
+SET previous_period_trend = previous.trend
+FROM (
+    SELECT id, LAG(bar_trend, 1, 'Bullish') OVER (PARTITION BY stock_symbol ORDER BY event_unix_timestamp) AS trend
+    FROM hourly_stock_bars
+) AS previous
+WHERE hourly_stock_bars.id = previous.id;
+
+ALTER TABLE fifteen_minute_stock_bars ADD COLUMN previous_period_trend TEXT NOT NULL DEFAULT 'Bullish';
+UPDATE fifteen_minute_stock_bars
+SET previous_period_trend = previous.trend
+FROM (
+    SELECT id, LAG(bar_trend, 1, 'Bullish') OVER (PARTITION BY stock_symbol ORDER BY event_unix_timestamp) AS trend
+    FROM fifteen_minute_stock_bars
+) AS previous
+WHERE fifteen_minute_stock_bars.id = previous.id;
+
+-- The old daily entry constructor swapped the nine day SMA and EMA
+UPDATE daily_stock_bars
+SET nine_day_sma = nine_day_ema, nine_day_ema = nine_day_sma;
@@ -0,0 +1,447 @@
+-- Add migration script here
+-- Feature columns become nullable and hold NULL while an indicator is warming up.
+-- SQLite cannot drop NOT NULL in place, so every bar table is rebuilt. Zeros within
+-- the first N bars of a symbol were written as warm-up sentinels and become NULL.
+
+CREATE TABLE monthly_stock_bars_rebuilt (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_datetime TEXT NOT NULL,
+    event_unix_timestamp INTEGER NOT NULL,
+    open_price REAL NOT NULL DEFAULT 0.0,
+    close_price REAL NOT NULL DEFAULT 0.0,
+    high_price REAL NOT NULL DEFAULT 0.0,
+    low_price REAL NOT NULL DEFAULT 0.0,
+    volume REAL NOT NULL DEFAULT 0.0,
+    volume_weighted_price REAL DEFAULT 0.0,
+    stock_symbol TEXT NOT NULL,
+    timeframe TEXT NOT NULL,
+    bar_trend TEXT NOT NULL,
+    buy_or_sell INTEGER NOT NULL,
+    next_period_price REAL NOT NULL,
+    next_period_trend TEXT NOT NULL,
+    next_period_unix_timestamp INTEGER NOT NULL,
+    next_period_event_datetime TEXT NOT NULL,
+    previous_period_trend TEXT NOT NULL DEFAULT 'Bullish',
+    ten_week_sma REAL,
+    ten_week_ema REAL,
+    ten_week_rsi REAL,
+    ten_week_high REAL,
+    ten_week_low REAL,
+    five_week_high REAL,
+    five_week_low REAL
+);
+
+INSERT INTO monthly_stock_bars_rebuilt (
+    id,
+    event_datetime,
+    event_unix_timestamp,
+    open_price,
+    close_price,
+    high_price,
+    low_price,
+    volume,
+    volume_weighted_price,
+    stock_symbol,
+    timeframe,
+    bar_trend,
+    buy_or_sell,
+    next_period_price,
+    next_period_trend,
+    next_period_unix_timestamp,
+    next_period_event_datetime,
+    previous_period_trend,
+    ten_week_sma,
+    ten_week_ema,
+    ten_week_rsi,
+    ten_week_high,
+    ten_week_low,
+    five_week_high,
+    five_week_low
+)
+SELECT
+    id,
+    event_datetime,
+    event_unix_timestamp,
+    open_price,
+    close_price,
+    high_price,
+    low_price,
+    volume,
+    volume_weighted_price,
+    stock_symbol,
+    timeframe,
+    bar_trend,
+    buy_or_sell,
+    next_period_price,
+    next_period_trend,
+    next_period_unix_timestamp,
+    next_period_event_datetime,
+    previous_period_trend,
+    CASE WHEN bar_index < 10 THEN NULLIF(ten_week_sma, 0.0) ELSE ten_week_sma END,
+    CASE WHEN bar_index < 10 THEN NULLIF(ten_week_ema, 0.0) ELSE ten_week_ema END,
+    CASE WHEN bar_index < 10 THEN NULLIF(ten_week_rsi, 0.0) ELSE ten_week_rsi END,
+    CASE WHEN bar_index < 10 THEN NULLIF(ten_week_high, 0.0) ELSE ten_week_high END,
+    CASE WHEN bar_index < 10 THEN NULLIF(ten_week_low, 0.0) ELSE ten_week_low END,
+    CASE WHEN bar_index < 5 THEN NULLIF(five_week_high, 0.0) ELSE five_week_high END,
+    CASE WHEN bar_index < 5 THEN NULLIF(five_week_low, 0.0) ELSE five_week_low END
+FROM (
+    SELECT *, ROW_NUMBER() OVER (PARTITION BY stock_symbol, timeframe ORDER BY event_unix_timestamp) - 1 AS bar_index
+    FROM monthly_stock_bars
+);
+
+DROP TABLE monthly_stock_bars;
+ALTER TABLE monthly_stock_bars_rebuilt RENAME TO monthly_stock_bars;
+
+CREATE UNIQUE INDEX IF NOT EXISTS monthly_stock_bars_symbol_timeframe_timestamp
+ON monthly_stock_bars (stock_symbol, timeframe, event_unix_timestamp);
+
+CREATE TABLE daily_stock_bars_rebuilt (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_datetime TEXT NOT NULL,
+    event_unix_timestamp INTEGER NOT NULL,
+    open_price REAL NOT NULL DEFAULT 0.0,
+    close_price REAL NOT NULL DEFAULT 0.0,
+    high_price REAL NOT NULL DEFAULT 0.0,
+    low_price REAL NOT NULL DEFAULT 0.0,
+    volume REAL NOT NULL DEFAULT 0.0,
+    volume_weighted_price REAL DEFAULT 0.0,
+    stock_symbol TEXT NOT NULL,
+    timeframe TEXT NOT NULL,
+    bar_trend TEXT NOT NULL,
+    buy_or_sell INTEGER NOT NULL,
+    next_period_price REAL NOT NULL,
+    next_period_trend TEXT NOT NULL,
+    next_period_unix_timestamp INTEGER NOT NULL,
+    next_period_event_datetime TEXT NOT NULL,
+    previous_period_trend TEXT NOT NULL DEFAULT 'Bullish',
+    hundred_day_sma REAL,
+    hundred_day_ema REAL,
+    fifty_day_sma REAL,
+    fifty_day_ema REAL,
+    twenty_day_sma REAL,
+    twenty_day_ema REAL,
+    nine_day_sma REAL,
+    nine_day_ema REAL,
+    hundred_day_high REAL,
+    hundred_day_low REAL,
+    fifty_day_high REAL,
+    fifty_day_low REAL,
+    ten_day_high REAL,
+    ten_day_low REAL,
+    fourteen_day_rsi REAL,
+    top_bollinger_band REAL,
+    middle_bollinger_band REAL,
+    bottom_bollinger_band REAL,
+    macd_signal REAL
+);
+
+INSERT INTO daily_stock_bars_rebuilt (
+    id,
+    event_datetime,
+    event_unix_timestamp,
+    open_price,
+    close_price,
+    high_price,
+    low_price,
+    volume,
+    volume_weighted_price,
+    stock_symbol,
+    timeframe,
+    bar_trend,
+    buy_or_sell,
+    next_period_price,
+    next_period_trend,
+    next_period_unix_timestamp,
+    next_period_event_datetime,
+    previous_period_trend,
+    hundred_day_sma,
+    hundred_day_ema,
+    fifty_day_sma,
+    fifty_day_ema,
+    twenty_day_sma,
+    twenty_day_ema,
+    nine_day_sma,
+    nine_day_ema,
+    hundred_day_high,
+    hundred_day_low,
+    fifty_day_high,
+    fifty_day_low,
+    ten_day_high,
+    ten_day_low,
+    fourteen_day_rsi,
+    top_bollinger_band,
+    middle_bollinger_band,
+    bottom_bollinger_band,
+    macd_signal
+)
+SELECT
+    id,
+    event_datetime,
+    event_unix_timestamp,
+    open_price,
+    close_price,
+    high_price,
+    low_price,
+    volume,
+    volume_weighted_price,
+    stock_symbol,
+    timeframe,
+    bar_trend,
+    buy_or_sell,
+    next_period_price,
+    next_period_trend,
+    next_period_unix_timestamp,
+    next_period_event_datetime,
+    previous_period_trend,
+    CASE WHEN bar_index < 100 THEN NULLIF(hundred_day_sma, 0.0) ELSE hundred_day_sma END,
+    CASE WHEN bar_index < 100 THEN NULLIF(hundred_day_ema, 0.0) ELSE hundred_day_ema END,
+    CASE WHEN bar_index < 50 THEN NULLIF(fifty_day_sma, 0.0) ELSE fifty_day_sma END,
+    CASE WHEN bar_index < 50 THEN NULLIF(fifty_day_ema, 0.0) ELSE fifty_day_ema END,
+    CASE WHEN bar_index < 20 THEN NULLIF(twenty_day_sma, 0.0) ELSE twenty_day_sma END,
+    CASE WHEN bar_index < 20 THEN NULLIF(twenty_day_ema, 0.0) ELSE twenty_day_ema END,
+    CASE WHEN bar_index < 9 THEN NULLIF(nine_day_sma, 0.0) ELSE nine_day_sma END,
+    CASE WHEN bar_index < 9 THEN NULLIF(nine_day_ema, 0.0) ELSE nine_day_ema END,
+    CASE WHEN bar_index < 100 THEN NULLIF(hundred_day_high, 0.0) ELSE hundred_day_high END,
+    CASE WHEN bar_index < 100 THEN NULLIF(hundred_day_low, 0.0) ELSE hundred_day_low END,
+    CASE WHEN bar_index < 50 THEN NULLIF(fifty_day_high, 0.0) ELSE fifty_day_high END,
+    CASE WHEN bar_index < 50 THEN NULLIF(fifty_day_low, 0.0) ELSE fifty_day_low END,
+    CASE WHEN bar_index < 10 THEN NULLIF(ten_day_high, 0.0) ELSE ten_day_high END,
+    CASE WHEN bar_index < 10 THEN NULLIF(ten_day_low, 0.0) ELSE ten_day_low END,
+    CASE WHEN bar_index < 14 THEN NULLIF(fourteen_day_rsi, 0.0) ELSE fourteen_day_rsi END,
+    CASE WHEN bar_index < 20 THEN NULLIF(top_bollinger_band, 0.0) ELSE top_bollinger_band END,
+    CASE WHEN bar_index < 20 THEN NULLIF(middle_bollinger_band, 0.0) ELSE middle_bollinger_band END,
+    CASE WHEN bar_index < 20 THEN NULLIF(bottom_bollinger_band, 0.0) ELSE bottom_bollinger_band END,
+    CASE WHEN bar_index < 35 THEN NULLIF(macd_signal, 0.0) ELSE macd_signal END
+FROM (
+    SELECT *, ROW_NUMBER() OVER (PARTITION BY stock_symbol, timeframe ORDER BY event_unix_timestamp) - 1 AS bar_index
+    FROM daily_stock_bars
+);
+
+DROP TABLE daily_stock_bars;
+ALTER TABLE daily_stock_bars_rebuilt RENAME TO daily_stock_bars;
+
+CREATE UNIQUE INDEX IF NOT EXISTS daily_stock_bars_symbol_timeframe_timestamp
+ON daily_stock_bars (stock_symbol, timeframe, event_unix_timestamp);
+
+CREATE TABLE hourly_stock_bars_rebuilt (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_datetime TEXT NOT NULL,
+    event_unix_timestamp INTEGER NOT NULL,
+    open_price REAL NOT NULL DEFAULT 0.0,
+    close_price REAL NOT NULL DEFAULT 0.0,
+    high_price REAL NOT NULL DEFAULT 0.0,
+    low_price REAL NOT NULL DEFAULT 0.0,
+    volume REAL NOT NULL DEFAULT 0.0,
+    volume_weighted_price REAL DEFAULT 0.0,
+    stock_symbol TEXT NOT NULL,
+    timeframe TEXT NOT NULL,
+    bar_trend TEXT NOT NULL,
+    buy_or_sell INTEGER NOT NULL,
+    next_period_price REAL NOT NULL,
+    next_period_trend TEXT NOT NULL,
+    next_period_unix_timestamp INTEGER NOT NULL,
+    next_period_event_datetime TEXT NOT NULL,
+    previous_period_trend TEXT NOT NULL DEFAULT 'Bullish',
+    five_period_sma REAL,
+    eight_period_sma REAL,
+    thirteen_period_sma REAL,
+    nine_period_rsi REAL,
+    top_bollinger_band REAL,
+    middle_bollinger_band REAL,
+    bottom_bollinger_band REAL,
+    twenty_period_high REAL,
+    twenty_period_low REAL,
+    eight_period_high REAL,
+    eight_period_low REAL,
+    five_period_high REAL,
+    five_period_low REAL
+);
+
+INSERT INTO hourly_stock_bars_rebuilt (
+    id,
+    event_datetime,
+    event_unix_timestamp,
+    open_price,
+    close_price,
+    high_price,
+    low_price,
+    volume,
+    volume_weighted_price,
+    stock_symbol,
+    timeframe,
+    bar_trend,
+    buy_or_sell,
+    next_period_price,
+    next_period_trend,
+    next_period_unix_timestamp,
+    next_period_event_datetime,
+    previous_period_trend,
+    five_period_sma,
+    eight_period_sma,
+    thirteen_period_sma,
+    nine_period_rsi,
+    top_bollinger_band,
+    middle_bollinger_band,
+    bottom_bollinger_band,
+    twenty_period_high,
+    twenty_period_low,
+    eight_period_high,
+    eight_period_low,
+    five_period_high,
+    five_period_low
+)
+SELECT
+    id,
+    event_datetime,
+    event_unix_timestamp,
+    open_price,
+    close_price,
+    high_price,
+    low_price,
+    volume,
+    volume_weighted_price,
+    stock_symbol,
+    timeframe,
+    bar_trend,
+    buy_or_sell,
+    next_period_price,
+    next_period_trend,
+    next_period_unix_timestamp,
+    next_period_event_datetime,
+    previous_period_trend,
+    CASE WHEN bar_index < 5 THEN NULLIF(five_period_sma, 0.0) ELSE five_period_sma END,
+    CASE WHEN bar_index < 8 THEN NULLIF(eight_period_sma, 0.0) ELSE eight_period_sma END,
+    CASE WHEN bar_index < 13 THEN NULLIF(thirteen_period_sma, 0.0) ELSE thirteen_period_sma END,
+    CASE WHEN bar_index < 9 THEN NULLIF(nine_period_rsi, 0.0) ELSE nine_period_rsi END,
+    CASE WHEN bar_index < 13 THEN NULLIF(top_bollinger_band, 0.0) ELSE top_bollinger_band END,
+    CASE WHEN bar_index < 13 THEN NULLIF(middle_bollinger_band, 0.0) ELSE middle_bollinger_band END,
+    CASE WHEN bar_index < 13 THEN NULLIF(bottom_bollinger_band, 0.0) ELSE bottom_bollinger_band END,
+    CASE WHEN bar_index < 20 THEN NULLIF(twenty_period_high, 0.0) ELSE twenty_period_high END,
+    CASE WHEN bar_index < 20 THEN NULLIF(twenty_period_low, 0.0) ELSE twenty_period_low END,
+    CASE WHEN bar_index < 8 THEN NULLIF(eight_period_high, 0.0) ELSE eight_period_high END,
+    CASE WHEN bar_index < 8 THEN NULLIF(eight_period_low, 0.0) ELSE eight_period_low END,
+    CASE WHEN bar_index < 5 THEN NULLIF(five_period_high, 0.0) ELSE five_period_high END,
+    CASE WHEN bar_index < 5 THEN NULLIF(five_period_low, 0.0) ELSE five_period_low END
+FROM (
+    SELECT *, ROW_NUMBER() OVER (PARTITION BY stock_symbol, timeframe ORDER BY event_unix_timestamp) - 1 AS bar_index
+    FROM hourly_stock_bars
+);
+
+DROP TABLE hourly_stock_bars;
+ALTER TABLE hourly_stock_bars_rebuilt RENAME TO hourly_stock_bars;
+
+CREATE UNIQUE INDEX IF NOT EXISTS hourly_stock_bars_symbol_timeframe_timestamp
+ON hourly_stock_bars (stock_symbol, timeframe, event_unix_timestamp);
+
+CREATE TABLE fifteen_minute_stock_bars_rebuilt (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_datetime TEXT NOT NULL,
+    event_unix_timestamp INTEGER NOT NULL,
+    open_price REAL NOT NULL DEFAULT 0.0,
+    close_price REAL NOT NULL DEFAULT 0.0,
+    high_price REAL NOT NULL DEFAULT 0.0,
+    low_price REAL NOT NULL DEFAULT 0.0,
+    volume REAL NOT NULL DEFAULT 0.0,
+    volume_weighted_price REAL DEFAULT 0.0,
+    stock_symbol TEXT NOT NULL,
+    timeframe TEXT NOT NULL,
+    bar_trend TEXT NOT NULL,
+    buy_or_sell INTEGER NOT NULL,
+    next_period_price REAL NOT NULL,
+    next_period_trend TEXT NOT NULL,
+    next_period_unix_timestamp INTEGER NOT NULL,
+    next_period_event_datetime TEXT NOT NULL,
+    previous_period_trend TEXT NOT NULL DEFAULT 'Bullish',
+    five_period_sma REAL,
+    eight_period_sma REAL,
+    thirteen_period_sma REAL,
+    twenty_period_ema REAL,
+    nine_period_rsi REAL,
+    top_bollinger_band REAL,
+    middle_bollinger_band REAL,
+    bottom_bollinger_band REAL,
+    twenty_period_high REAL,
+    twenty_period_low REAL,
+    eight_period_high REAL,
+    eight_period_low REAL,
+    five_period_high REAL,
+    five_period_low REAL
+);
+
+INSERT INTO fifteen_minute_stock_bars_rebuilt (
+    id,
+    event_datetime,
+    event_unix_timestamp,
+    open_price,
+    close_price,
+    high_price,
+    low_price,
+    volume,
+    volume_weighted_price,
+    stock_symbol,
+    timeframe,
+    bar_trend,
+    buy_or_sell,
+    next_period_price,
+    next_period_trend,
+    next_period_unix_timestamp,
+    next_period_event_datetime,
+    previous_period_trend,
+    five_period_sma,
+    eight_period_sma,
+    thirteen_period_sma,
+    twenty_period_ema,
+    nine_period_rsi,
+    top_bollinger_band,
+    middle_bollinger_band,
+    bottom_bollinger_band,
+    twenty_period_high,
+    twenty_period_low,
+    eight_period_high,
+    eight_period_low,
+    five_period_high,
+    five_period_low
+)
+SELECT
+    id,
+    event_datetime,
+    event_unix_timestamp,
+    open_price,
+    close_price,
+    high_price,
+    low_price,
+    volume,
+    volume_weighted_price,
+    stock_symbol,
+    timeframe,
+    bar_trend,
+    buy_or_sell,
+    next_period_price,
+    next_period_trend,
+    next_period_unix_timestamp,
+    next_period_event_datetime,
+    previous_period_trend,
+    CASE WHEN bar_index < 5 THEN NULLIF(five_period_sma, 0.0) ELSE five_period_sma END,
+    CASE WHEN bar_index < 8 THEN NULLIF(eight_period_sma, 0.0) ELSE eight_period_sma END,
+    CASE WHEN bar_index < 13 THEN NULLIF(thirteen_period_sma, 0.0) ELSE thirteen_period_sma END,
+    CASE WHEN bar_index < 20 THEN NULLIF(twenty_period_ema, 0.0) ELSE twenty_period_ema END,
+    CASE WHEN bar_index < 9 THEN NULLIF(nine_period_rsi, 0.0) ELSE nine_period_rsi END,
+    CASE WHEN bar_index < 13 THEN NULLIF(top_bollinger_band, 0.0) ELSE top_bollinger_band END,
+    CASE WHEN bar_index < 13 THEN NULLIF(middle_bollinger_band, 0.0) ELSE middle_bollinger_band END,
+    CASE WHEN bar_index < 13 THEN NULLIF(bottom_bollinger_band, 0.0) ELSE bottom_bollinger_band END,
+    CASE WHEN bar_index < 20 THEN NULLIF(twenty_period_high, 0.0) ELSE twenty_period_high END,
+    CASE WHEN bar_index < 20 THEN NULLIF(twenty_period_low, 0.0) ELSE twenty_period_low END,
+    CASE WHEN bar_index < 8 THEN NULLIF(eight_period_high, 0.0) ELSE eight_period_high END,
+    CASE WHEN bar_index < 8 THEN NULLIF(eight_period_low, 0.0) ELSE eight_period_low END,
+    CASE WHEN bar_index < 5 THEN NULLIF(five_period_high, 0.0) ELSE five_period_high END,
+    CASE WHEN bar_index < 5 THEN NULLIF(five_period_low, 0.0) ELSE five_period_low END
+FROM (
+    SELECT *, ROW_NUMBER() OVER (PARTITION BY stock_symbol, timeframe ORDER BY event_unix_timestamp) - 1 AS bar_index
+    FROM fifteen_minute_stock_bars
+);
+
+DROP TABLE fifteen_minute_stock_bars;
+ALTER TABLE fifteen_minute_stock_bars_rebuilt RENAME TO fifteen_minute_stock_bars;
+
+CREATE UNIQUE INDEX IF NOT EXISTS fifteen_minute_stock_bars_symbol_timeframe_timestamp
+ON fifteen_minute_stock_bars (stock_symbol, timeframe, event_unix_timestamp);
@@ -0,0 +1,16 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS corporate_actions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    stock_symbol TEXT NOT NULL,
+    action_type TEXT NOT NULL CHECK (action_type IN ('split', 'dividend')),
+    ex_date TEXT NOT NULL,
+    split_ratio REAL,
+    cash_amount REAL,
+    UNIQUE (stock_symbol, action_type, ex_date)
+);
+
+-- Bars stored so far were not adjusted
+ALTER TABLE monthly_stock_bars ADD COLUMN adjustment_mode TEXT NOT NULL DEFAULT 'raw';
+ALTER TABLE daily_stock_bars ADD COLUMN adjustment_mode TEXT NOT NULL DEFAULT 'raw';
+ALTER TABLE hourly_stock_bars ADD COLUMN adjustment_mode TEXT NOT NULL DEFAULT 'raw';
+ALTER TABLE fifteen_minute_stock_bars ADD COLUMN adjustment_mode TEXT NOT NULL DEFAULT 'raw';
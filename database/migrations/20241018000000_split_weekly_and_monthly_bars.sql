@@ -0,0 +1,42 @@
+-- Add migration script here
+-- monthly_stock_bars has only ever held weekly bars with ten/five-week features.
+-- It becomes weekly_stock_bars and a real monthly table takes over the name.
+
+ALTER TABLE monthly_stock_bars RENAME TO weekly_stock_bars;
+
+DROP INDEX IF EXISTS monthly_stock_bars_symbol_timeframe_timestamp;
+CREATE UNIQUE INDEX IF NOT EXISTS weekly_stock_bars_symbol_timeframe_timestamp
+ON weekly_stock_bars (stock_symbol, timeframe, event_unix_timestamp);
+
+CREATE TABLE IF NOT EXISTS monthly_stock_bars (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_datetime TEXT NOT NULL,
+    event_unix_timestamp INTEGER NOT NULL,
+    open_price REAL NOT NULL DEFAULT 0.0,
+    close_price REAL NOT NULL DEFAULT 0.0,
+    high_price REAL NOT NULL DEFAULT 0.0,
+    low_price REAL NOT NULL DEFAULT 0.0,
+    volume REAL NOT NULL DEFAULT 0.0,
+    volume_weighted_price REAL DEFAULT 0.0,
+    stock_symbol TEXT NOT NULL,
+    timeframe TEXT NOT NULL,
+    bar_trend TEXT NOT NULL,
+    buy_or_sell INTEGER NOT NULL,
+    next_period_price REAL NOT NULL,
+    next_period_trend TEXT NOT NULL,
+    next_period_unix_timestamp INTEGER NOT NULL,
+    next_period_event_datetime TEXT NOT NULL,
+    previous_period_trend TEXT NOT NULL DEFAULT 'Bullish',
+    adjustment_mode TEXT NOT NULL DEFAULT 'raw',
+    twelve_month_sma REAL,
+    twenty_four_month_sma REAL,
+    twelve_month_ema REAL,
+    twelve_month_rsi REAL,
+    twelve_month_high REAL,
+    twelve_month_low REAL,
+    six_month_high REAL,
+    six_month_low REAL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS monthly_stock_bars_symbol_timeframe_timestamp
+ON monthly_stock_bars (stock_symbol, timeframe, event_unix_timestamp);
@@ -0,0 +1,2 @@
+DROP TABLE watchlist_members;
+DROP TABLE watchlists;
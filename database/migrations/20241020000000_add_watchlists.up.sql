@@ -0,0 +1,221 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS watchlists (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT
+);
+
+CREATE TABLE IF NOT EXISTS watchlist_members (
+    watchlist_id INTEGER NOT NULL REFERENCES watchlists (id) ON DELETE CASCADE,
+    stock_symbol TEXT NOT NULL,
+    PRIMARY KEY (watchlist_id, stock_symbol)
+);
+
+CREATE INDEX IF NOT EXISTS watchlist_members_stock_symbol_idx ON watchlist_members (stock_symbol);
+
+-- Seed the lists that used to be constants in data/src/watchlist.rs
+INSERT OR IGNORE INTO watchlists (name, description) VALUES
+    ('finance', 'XLF sector'),
+    ('tech', 'XLK sector'),
+    ('energy', 'XLE sector'),
+    ('utilities', 'XLU sector'),
+    ('real_estate', 'XLRE sector'),
+    ('communication', 'XLC sector'),
+    ('metals_mining', 'XME sector'),
+    ('consumer_staples', 'XLP sector'),
+    ('materials', 'XLB sector'),
+    ('industrials', 'XLI sector'),
+    ('healthcare', 'XLV sector'),
+    ('consumer_discretionary', 'XLY sector'),
+    ('semiconductors', 'SMH sector'),
+    ('transportation', 'XTN sector'),
+    ('emerging_markets', 'VWO sector'),
+    ('etfs', 'Sector and index ETFs'),
+    ('batch_one', 'Ingestion batch'),
+    ('batch_two', 'Ingestion batch'),
+    ('batch_three', 'Ingestion batch'),
+    ('batch_four', 'Ingestion batch'),
+    ('batch_five', 'Ingestion batch'),
+    ('batch_six', 'Ingestion batch'),
+    ('batch_seven', 'Ingestion batch'),
+    ('batch_eight', 'Ingestion batch');
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'JPM', 'WFC', 'C', 'GS', 'MS', 'AXP', 'V', 'MA', 'BLK', 'SCHW', 'PRU', 'MET', 'AIG', 'COF',
+    'TD', 'RY', 'BMO', 'BNS', 'CM', 'ITUB', 'BBD', 'LYG', 'BCS', 'ESQ', 'KEY', 'ACGL', 'ARES',
+    'RF', 'XLF'
+)) AS symbols WHERE watchlists.name = 'finance';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'AAPL', 'MSFT', 'GOOG', 'META', 'AMZN', 'TSLA', 'NVDA', 'INTC', 'CRM', 'ORCL', 'CSCO', 'ADBE',
+    'IBM', 'TXN', 'QCOM', 'SAP', 'SHOP', 'ASML', 'INTU', 'AMD', 'MU', 'SQ', 'ADSK', 'TEAM', 'WDAY',
+    'DELL', 'HPQ', 'ARRY', 'TTMI', 'MXL', 'NTCT', 'PLTR', 'SOFI', 'PINS', 'COIN', 'ARLO', 'UI',
+    'Z', 'SE', 'SONY', 'TTWO', 'XLK'
+)) AS symbols WHERE watchlists.name = 'tech';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'XOM', 'CVX', 'COP', 'BP', 'SLB', 'EOG', 'OXY', 'VLO', 'MPC', 'PSX', 'KMI', 'WMB', 'BKR',
+    'HES', 'APA', 'FANG', 'MRO', 'CNQ', 'PBR', 'YPF', 'ENB', 'KOS', 'MPLX', 'BTU', 'PBF', 'CEG',
+    'NRG', 'CVI', 'PTEN', 'WDS', 'FSLR', 'XLE'
+)) AS symbols WHERE watchlists.name = 'energy';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'NEE', 'DUK', 'D', 'SO', 'EXC', 'AEP', 'SRE', 'XEL', 'PEG', 'WEC', 'DTE', 'ED', 'EIX', 'AEE',
+    'CNP', 'CMS', 'AWK', 'ETR', 'EVRG', 'AES', 'LNT', 'NI', 'PNW', 'OGE', 'NWE', 'IDA', 'POR',
+    'AVA', 'MGEE', 'BEP', 'XLU'
+)) AS symbols WHERE watchlists.name = 'utilities';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'AVB', 'EQR', 'DLR', 'VTR', 'SBAC', 'O', 'REG', 'EXR', 'ARE', 'UDR', 'ESS', 'ELS', 'MAA',
+    'CPT', 'IRM', 'RHP', 'KRC', 'NHI', 'OHI', 'XLRE'
+)) AS symbols WHERE watchlists.name = 'real_estate';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'DIS', 'CMCSA', 'VZ', 'T', 'TMUS', 'FOX', 'OMC', 'TME', 'MTCH', 'IAC', 'NTES', 'BIDU', 'ROKU',
+    'LYV', 'IQ', 'VIAV', 'EA', 'SPOT', 'NFLX', 'XLC'
+)) AS symbols WHERE watchlists.name = 'communication';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'AEM', 'WPM', 'TECK', 'SCCO', 'FNV', 'KGC', 'PAAS', 'AG', 'IAG', 'BTG', 'HBM', 'CCJ', 'AA',
+    'X', 'CLF', 'STLD', 'RS', 'CMC', 'WOR', 'SAND', 'ARCH', 'HL', 'SGML', 'SMID', 'EQT', 'SIL',
+    'PSLV', 'XME', 'LIT', 'REMX', 'SLX'
+)) AS symbols WHERE watchlists.name = 'metals_mining';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'PG', 'KO', 'PEP', 'PM', 'WMT', 'MO', 'COST', 'MDLZ', 'CL', 'KMB', 'STZ', 'EL', 'GIS', 'KR',
+    'ADM', 'MNST', 'TSN', 'SYY', 'DEO', 'CPB', 'CLX', 'HSY', 'CAG', 'HRL', 'MKC', 'CHD', 'CCEP',
+    'SJM', 'LW', 'XLP'
+)) AS symbols WHERE watchlists.name = 'consumer_staples';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'LIN', 'APD', 'ECL', 'PPG', 'SHW', 'DOW', 'LYB', 'CTVA', 'DD', 'AVY', 'IP', 'CHX', 'GPRE',
+    'FCX', 'NUE', 'NEM', 'XLB'
+)) AS symbols WHERE watchlists.name = 'materials';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'MMM', 'GE', 'HON', 'UNP', 'CAT', 'RTX', 'DE', 'EMR', 'ITW', 'NSC', 'LMT', 'GD', 'ADP', 'CSX',
+    'CMI', 'NOC', 'WM', 'ETN', 'PCAR', 'IR', 'ROP', 'PH', 'TDG', 'DOV', 'SWK', 'TXT', 'CTAS',
+    'CPRT', 'GWW', 'RSG', 'AVY', 'XYL', 'ALLE', 'MAS', 'AME', 'WAB', 'WFRD', 'ACA', 'CLH', 'FIX',
+    'SYM', 'XLI'
+)) AS symbols WHERE watchlists.name = 'industrials';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'JNJ', 'PFE', 'MRK', 'ABBV', 'ABT', 'MDT', 'AMGN', 'BMY', 'GILD', 'TMO', 'LLY', 'DHR', 'SYK',
+    'BSX', 'AZN', 'CVS', 'ISRG', 'ZTS', 'BDX', 'CI', 'VRTX', 'HCA', 'ALGN', 'EW', 'CNC', 'REGN',
+    'IQV', 'HUM', 'A', 'WAT', 'BAX', 'BIIB', 'HSIC', 'RMD', 'SAVA', 'KROS', 'AIRS', 'XLV'
+)) AS symbols WHERE watchlists.name = 'healthcare';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'HD', 'MCD', 'NKE', 'SBUX', 'LOW', 'BKNG', 'TJX', 'GM', 'F', 'EBAY', 'ETSY', 'YUM', 'ORLY',
+    'ROST', 'DG', 'AZO', 'DLTR', 'RCL', 'CCL', 'NCLH', 'MGM', 'LVS', 'WYNN', 'BBY', 'ULTA', 'GRMN',
+    'PVH', 'RL', 'GPS', 'TPR', 'JWN', 'DRVN', 'CHDN', 'RIVN', 'DKNG', 'BLBD', 'Z', 'RVLV', 'LI',
+    'TM', 'XLY'
+)) AS symbols WHERE watchlists.name = 'consumer_discretionary';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'INTC', 'TSM', 'QCOM', 'ASML', 'TXN', 'AMD', 'MU', 'ADI', 'NXPI', 'KLAC', 'MCHP', 'LRCX',
+    'MRVL', 'AMAT', 'SWKS', 'SNPS', 'CDNS', 'MSCI', 'TER', 'QRVO', 'ON', 'UMC', 'SMTC', 'MPWR',
+    'ENPH', 'SEDG', 'IPGP', 'COHU', 'ASX', 'GSIT', 'SMH'
+)) AS symbols WHERE watchlists.name = 'semiconductors';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'UPS', 'FDX', 'DAL', 'AAL', 'LUV', 'UAL', 'CHRW', 'JBHT', 'ODFL', 'LSTR', 'EXPD', 'R', 'MATX',
+    'SAIA', 'ALK', 'SAVE', 'JBLU', 'ZTO', 'HUBG', 'PATK', 'ULH', 'ARCB', 'DSKE', 'PTSI', 'RLGT',
+    'PANL', 'UBER', 'CVLG', 'GLNG', 'XTN'
+)) AS symbols WHERE watchlists.name = 'transportation';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'BABA', 'HDB', 'GGAL', 'BMA', 'BBAR', 'VALE', 'ITUB', 'PBR', 'GGB', 'SBS', 'EBR', 'ASR', 'AMX',
+    'BAP', 'BVN', 'BCH', 'VWO'
+)) AS symbols WHERE watchlists.name = 'emerging_markets';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'XLF', 'XLRE', 'XLC', 'XME', 'XLP', 'XLB', 'XLE', 'XLI', 'XLK', 'XLV', 'XLY', 'XTN', 'XLU',
+    'SMH', 'VWO', 'QQQ', 'SPY', 'VTI', 'VEA', 'SCHF', 'VOO', 'VCLT', 'VNQ', 'VDE', 'VTV', 'VUG',
+    'VHT', 'VAW', 'VIS', 'VFH', 'VPU', 'VB', 'VO', 'VYM', 'XPO'
+)) AS symbols WHERE watchlists.name = 'etfs';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'CRM', 'SO', 'ZTO', 'COP', 'CCL', 'TJX', 'AMX', 'NFLX', 'PRU', 'WYNN', 'ULH', 'T', 'META',
+    'MDLZ', 'SOFI', 'AEE', 'SBS', 'CMI', 'UDR', 'MMM', 'TTMI', 'OGE', 'GWW', 'DKNG', 'LRCX', 'SQ',
+    'UI', 'KR', 'JBHT', 'AIG', 'IP', 'EQT', 'CTVA', 'VOO', 'HON', 'CLX', 'XLE', 'MPC', 'SMH',
+    'SCHF', 'O', 'XME', 'ASR', 'REG', 'ADP', 'XLY', 'PTEN', 'SONY', 'FOX', 'VUG', 'ADM', 'HSIC',
+    'CPT', 'AES', 'ZTS', 'JWN', 'BLBD', 'DG', 'SLB', 'DTE', 'EXC', 'GGB', 'JBLU', 'PBF', 'KO'
+)) AS symbols WHERE watchlists.name = 'batch_one';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'PM', 'PATK', 'KEY', 'EXR', 'PG', 'TMO', 'DHR', 'SWKS', 'TDG', 'NWE', 'LYB', 'CEG', 'IR',
+    'CAG', 'SAVA', 'SMID', 'BLK', 'ECL', 'VTR', 'GS', 'TTWO', 'MA', 'LYV', 'SJM', 'FCX', 'BSX',
+    'Z', 'NSC', 'XLB', 'C', 'DE', 'AVB', 'SIL', 'EIX', 'RTX', 'CPRT', 'UNP', 'ETSY', 'LUV', 'XLRE',
+    'AZO', 'ALGN', 'EOG', 'VEA', 'APA', 'STLD', 'ELS', 'ESS', 'PANL', 'EBR', 'BMY', 'BEP', 'WAT',
+    'AMZN', 'SPY', 'MRO', 'NTES', 'AEM', 'CCJ', 'CDNS', 'XLV', 'CTAS', 'PSLV', 'BABA', 'SCHW'
+)) AS symbols WHERE watchlists.name = 'batch_two';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'ED', 'BVN', 'HCA', 'BMA', 'ACA', 'BTG', 'LMT', 'MXL', 'FDX', 'INTC', 'ABBV', 'ROST', 'SHW',
+    'EVRG', 'VDE', 'RIVN', 'PBR', 'IPGP', 'BCS', 'R', 'TM', 'VWO', 'ARLO', 'SHOP', 'XOM', 'PNW',
+    'XLI', 'SLX', 'QCOM', 'LW', 'IQV', 'EL', 'PFE', 'DUK', 'ULTA', 'AAPL', 'SPOT', 'WDS', 'LLY',
+    'INTU', 'V', 'IDA', 'NHI', 'ETN', 'NVDA', 'ITW', 'ROP', 'TSLA', 'OHI', 'COIN', 'JNJ', 'BIDU',
+    'WEC', 'COHU', 'GSIT', 'ABT', 'TMUS', 'MSCI', 'WPM', 'WMT', 'FIX', 'TD', 'SAIA', 'BNS', 'VHT'
+)) AS symbols WHERE watchlists.name = 'batch_three';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'XLF', 'NCLH', 'AA', 'DEO', 'CHDN', 'HD', 'MSFT', 'VAW', 'CM', 'PPG', 'EQR', 'APD', 'VALE',
+    'ISRG', 'VYM', 'VTI', 'MKC', 'BMO', 'X', 'RCL', 'CSCO', 'UBER', 'ARCB', 'SAP', 'HUBG', 'ADSK',
+    'SYM', 'SRE', 'ASX', 'BAP', 'WFC', 'TECK', 'GRMN', 'HDB', 'PINS', 'NTCT', 'RLGT', 'TME',
+    'WDAY', 'ADI', 'BBAR', 'CAT', 'AIRS', 'SAVE', 'DSKE', 'ORLY', 'AMD', 'PVH', 'ASML', 'AAL',
+    'GD', 'A', 'KLAC', 'BBY', 'VTV', 'EW', 'BKNG', 'MAA', 'ROKU', 'CHX', 'CVX', 'LIT', 'GIS'
+)) AS symbols WHERE watchlists.name = 'batch_four';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'SYK', 'TER', 'MGEE', 'ON', 'AVA', 'SBAC', 'RF', 'VB', 'KRC', 'CMCSA', 'BDX', 'ADBE', 'MS',
+    'DLR', 'QQQ', 'RY', 'XEL', 'ITUB', 'NOC', 'BAX', 'RVLV', 'MAS', 'DRVN', 'HL', 'MET', 'HPQ',
+    'CPB', 'IQ', 'MO', 'SGML', 'YPF', 'COST', 'EXPD', 'GPS', 'AVY', 'KOS', 'PAAS', 'NEM', 'IBM',
+    'TXN', 'ORCL', 'SWK', 'BP', 'CHRW', 'ENB', 'UMC', 'TEAM', 'TSN', 'NEE', 'XYL', 'LI', 'VIS',
+    'WAB', 'CL', 'IAC', 'AWK', 'HBM', 'XLP', 'CLH', 'AEP', 'ARCH', 'LSTR', 'PTSI', 'VZ', 'XLK'
+)) AS symbols WHERE watchlists.name = 'batch_five';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'ACGL', 'CSX', 'MCD', 'HUM', 'MU', 'WM', 'MRVL', 'AMAT', 'DOW', 'NXPI', 'COF', 'EBAY', 'POR',
+    'DD', 'HSY', 'KROS', 'CI', 'FSLR', 'CVLG', 'XPO', 'XLC', 'MRK', 'MCHP', 'VFH', 'OMC', 'BKR',
+    'AXP', 'CVI', 'ARES', 'KMI', 'SEDG', 'SAND', 'GLNG', 'GILD', 'STZ', 'VO', 'CLF', 'CHD', 'BCH',
+    'SE', 'DLTR', 'CVS', 'HES', 'PH', 'IAG', 'MDT', 'MNST', 'WFRD', 'AZN', 'TXT', 'D', 'VRTX',
+    'UAL', 'MGM', 'SMTC', 'PEG', 'MPLX', 'CMC', 'REGN', 'DIS', 'BBD', 'CNC', 'AMGN', 'VCLT', 'AME'
+)) AS symbols WHERE watchlists.name = 'batch_six';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'DOV', 'EA', 'ENPH', 'WMB', 'NUE', 'HRL', 'SYY', 'RMD', 'QRVO', 'LNT', 'PEP', 'ARRY', 'NKE',
+    'UPS', 'CNQ', 'ETR', 'SNPS', 'MPWR', 'LVS', 'RSG', 'NI', 'LIN', 'VNQ', 'GPRE', 'PCAR', 'GM',
+    'YUM', 'FNV', 'DELL', 'XTN', 'KMB', 'VPU', 'GOOG', 'GE', 'GGAL', 'BTU', 'SCCO', 'BIIB', 'SBUX',
+    'CMS', 'TSM', 'VIAV', 'OXY', 'RS', 'CNP', 'JPM', 'LYG', 'ESQ', 'VLO', 'RHP', 'IRM', 'PSX',
+    'WOR', 'EMR', 'ALLE', 'TPR', 'PLTR', 'CCEP', 'MATX', 'REMX', 'FANG', 'NRG', 'KGC', 'LOW',
+    'MTCH', 'XLU', 'AG', 'ALK', 'DAL', 'F', 'ARE', 'RL', 'ODFL'
+)) AS symbols WHERE watchlists.name = 'batch_seven';
+
+INSERT OR IGNORE INTO watchlist_members (watchlist_id, stock_symbol)
+SELECT watchlists.id, symbols.value FROM watchlists, json_each(json_array(
+    'DIA', 'VTI', 'IWM', 'QQQ', 'GSG', 'GOLD', 'URA', 'INDA', 'EZA', 'EIS', 'THD', 'PHO', 'ARGT'
+)) AS symbols WHERE watchlists.name = 'batch_eight';
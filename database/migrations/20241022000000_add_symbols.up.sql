@@ -0,0 +1,364 @@
+-- Add migration script here
+-- Reference data for every ticker. first_bar_date and last_bar_date are kept
+-- up to date by the bar inserts.
+CREATE TABLE IF NOT EXISTS symbols (
+    ticker TEXT PRIMARY KEY,
+    name TEXT,
+    asset_class TEXT NOT NULL DEFAULT 'stock' CHECK (asset_class IN ('stock', 'etf')),
+    sector TEXT,
+    benchmark_etf TEXT,
+    exchange TEXT,
+    active INTEGER NOT NULL DEFAULT 1,
+    first_bar_date TEXT,
+    last_bar_date TEXT
+);
+
+CREATE INDEX IF NOT EXISTS symbols_sector ON symbols (sector);
+
+-- Sectors and benchmarks of the seeded watchlists. A ticker on several lists
+-- takes the first one.
+INSERT OR IGNORE INTO symbols (ticker, sector, benchmark_etf)
+SELECT watchlist_members.stock_symbol, sectors.sector, sectors.benchmark_etf
+FROM watchlist_members
+JOIN watchlists ON watchlists.id = watchlist_members.watchlist_id
+JOIN (
+    SELECT 1 AS priority, 'finance' AS watchlist, 'Financials' AS sector, 'XLF' AS benchmark_etf
+    UNION ALL SELECT 2, 'tech', 'Information Technology', 'XLK'
+    UNION ALL SELECT 3, 'energy', 'Energy', 'XLE'
+    UNION ALL SELECT 4, 'utilities', 'Utilities', 'XLU'
+    UNION ALL SELECT 5, 'real_estate', 'Real Estate', 'XLRE'
+    UNION ALL SELECT 6, 'communication', 'Communication Services', 'XLC'
+    UNION ALL SELECT 7, 'metals_mining', 'Materials', 'XME'
+    UNION ALL SELECT 8, 'consumer_staples', 'Consumer Staples', 'XLP'
+    UNION ALL SELECT 9, 'materials', 'Materials', 'XLB'
+    UNION ALL SELECT 10, 'industrials', 'Industrials', 'XLI'
+    UNION ALL SELECT 11, 'healthcare', 'Health Care', 'XLV'
+    UNION ALL SELECT 12, 'consumer_discretionary', 'Consumer Discretionary', 'XLY'
+    UNION ALL SELECT 13, 'semiconductors', 'Information Technology', 'SMH'
+    UNION ALL SELECT 14, 'transportation', 'Industrials', 'XTN'
+    UNION ALL SELECT 15, 'emerging_markets', NULL, 'VWO'
+) AS sectors ON sectors.watchlist = watchlists.name
+ORDER BY sectors.priority;
+
+-- Every other ticker on a watchlist or in a bar table
+INSERT OR IGNORE INTO symbols (ticker)
+SELECT stock_symbol FROM watchlist_members
+UNION SELECT stock_symbol FROM monthly_stock_bars
+UNION SELECT stock_symbol FROM weekly_stock_bars
+UNION SELECT stock_symbol FROM daily_stock_bars
+UNION SELECT stock_symbol FROM hourly_stock_bars
+UNION SELECT stock_symbol FROM fifteen_minute_stock_bars;
+
+UPDATE symbols SET asset_class = 'etf', benchmark_etf = NULL
+WHERE ticker IN (SELECT stock_symbol FROM watchlist_members
+    JOIN watchlists ON watchlists.id = watchlist_members.watchlist_id
+    WHERE watchlists.name = 'etfs')
+OR ticker IN (
+    'DIA', 'IWM', 'GSG', 'URA', 'INDA', 'EZA', 'EIS', 'THD', 'PHO', 'ARGT', 'SIL', 'LIT', 'REMX',
+    'SLX'
+);
+
+-- SQLite cannot add a foreign key to an existing table, so each bar table is
+-- rebuilt. Feature columns added from the config since the last migration are
+-- not carried over, `cli database sync-features` adds them back and a re-ingest
+-- fills them.
+
+CREATE TABLE monthly_stock_bars_with_symbols (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_datetime TEXT NOT NULL,
+    event_unix_timestamp INTEGER NOT NULL,
+    open_price REAL NOT NULL DEFAULT 0.0,
+    close_price REAL NOT NULL DEFAULT 0.0,
+    high_price REAL NOT NULL DEFAULT 0.0,
+    low_price REAL NOT NULL DEFAULT 0.0,
+    volume REAL NOT NULL DEFAULT 0.0,
+    volume_weighted_price REAL DEFAULT 0.0,
+    stock_symbol TEXT NOT NULL,
+    timeframe TEXT NOT NULL,
+    bar_trend TEXT NOT NULL,
+    buy_or_sell INTEGER NOT NULL,
+    next_period_price REAL NOT NULL,
+    next_period_trend TEXT NOT NULL,
+    next_period_unix_timestamp INTEGER NOT NULL,
+    next_period_event_datetime TEXT NOT NULL,
+    previous_period_trend TEXT NOT NULL DEFAULT 'Bullish',
+    adjustment_mode TEXT NOT NULL DEFAULT 'raw',
+    twelve_month_sma REAL,
+    twenty_four_month_sma REAL,
+    twelve_month_ema REAL,
+    twelve_month_rsi REAL,
+    twelve_month_high REAL,
+    twelve_month_low REAL,
+    six_month_high REAL,
+    six_month_low REAL,
+    FOREIGN KEY (stock_symbol) REFERENCES symbols (ticker) ON UPDATE CASCADE
+);
+INSERT INTO monthly_stock_bars_with_symbols (
+    id, event_datetime, event_unix_timestamp, open_price, close_price, high_price, low_price,
+    volume, volume_weighted_price, stock_symbol, timeframe, bar_trend, buy_or_sell,
+    next_period_price, next_period_trend, next_period_unix_timestamp,
+    next_period_event_datetime, previous_period_trend, adjustment_mode, twelve_month_sma,
+    twenty_four_month_sma, twelve_month_ema, twelve_month_rsi, twelve_month_high,
+    twelve_month_low, six_month_high, six_month_low
+)
+SELECT
+    id, event_datetime, event_unix_timestamp, open_price, close_price, high_price, low_price,
+    volume, volume_weighted_price, stock_symbol, timeframe, bar_trend, buy_or_sell,
+    next_period_price, next_period_trend, next_period_unix_timestamp,
+    next_period_event_datetime, previous_period_trend, adjustment_mode, twelve_month_sma,
+    twenty_four_month_sma, twelve_month_ema, twelve_month_rsi, twelve_month_high,
+    twelve_month_low, six_month_high, six_month_low
+FROM monthly_stock_bars;
+DROP TABLE monthly_stock_bars;
+ALTER TABLE monthly_stock_bars_with_symbols RENAME TO monthly_stock_bars;
+CREATE UNIQUE INDEX IF NOT EXISTS monthly_stock_bars_symbol_timeframe_timestamp
+ON monthly_stock_bars (stock_symbol, timeframe, event_unix_timestamp);
+
+CREATE TABLE weekly_stock_bars_with_symbols (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_datetime TEXT NOT NULL,
+    event_unix_timestamp INTEGER NOT NULL,
+    open_price REAL NOT NULL DEFAULT 0.0,
+    close_price REAL NOT NULL DEFAULT 0.0,
+    high_price REAL NOT NULL DEFAULT 0.0,
+    low_price REAL NOT NULL DEFAULT 0.0,
+    volume REAL NOT NULL DEFAULT 0.0,
+    volume_weighted_price REAL DEFAULT 0.0,
+    stock_symbol TEXT NOT NULL,
+    timeframe TEXT NOT NULL,
+    bar_trend TEXT NOT NULL,
+    buy_or_sell INTEGER NOT NULL,
+    next_period_price REAL NOT NULL,
+    next_period_trend TEXT NOT NULL,
+    next_period_unix_timestamp INTEGER NOT NULL,
+    next_period_event_datetime TEXT NOT NULL,
+    previous_period_trend TEXT NOT NULL DEFAULT 'Bullish',
+    adjustment_mode TEXT NOT NULL DEFAULT 'raw',
+    ten_week_sma REAL,
+    ten_week_ema REAL,
+    ten_week_rsi REAL,
+    ten_week_high REAL,
+    ten_week_low REAL,
+    five_week_high REAL,
+    five_week_low REAL,
+    FOREIGN KEY (stock_symbol) REFERENCES symbols (ticker) ON UPDATE CASCADE
+);
+INSERT INTO weekly_stock_bars_with_symbols (
+    id, event_datetime, event_unix_timestamp, open_price, close_price, high_price, low_price,
+    volume, volume_weighted_price, stock_symbol, timeframe, bar_trend, buy_or_sell,
+    next_period_price, next_period_trend, next_period_unix_timestamp,
+    next_period_event_datetime, previous_period_trend, adjustment_mode, ten_week_sma,
+    ten_week_ema, ten_week_rsi, ten_week_high, ten_week_low, five_week_high, five_week_low
+)
+SELECT
+    id, event_datetime, event_unix_timestamp, open_price, close_price, high_price, low_price,
+    volume, volume_weighted_price, stock_symbol, timeframe, bar_trend, buy_or_sell,
+    next_period_price, next_period_trend, next_period_unix_timestamp,
+    next_period_event_datetime, previous_period_trend, adjustment_mode, ten_week_sma,
+    ten_week_ema, ten_week_rsi, ten_week_high, ten_week_low, five_week_high, five_week_low
+FROM weekly_stock_bars;
+DROP TABLE weekly_stock_bars;
+ALTER TABLE weekly_stock_bars_with_symbols RENAME TO weekly_stock_bars;
+CREATE UNIQUE INDEX IF NOT EXISTS weekly_stock_bars_symbol_timeframe_timestamp
+ON weekly_stock_bars (stock_symbol, timeframe, event_unix_timestamp);
+
+CREATE TABLE daily_stock_bars_with_symbols (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_datetime TEXT NOT NULL,
+    event_unix_timestamp INTEGER NOT NULL,
+    open_price REAL NOT NULL DEFAULT 0.0,
+    close_price REAL NOT NULL DEFAULT 0.0,
+    high_price REAL NOT NULL DEFAULT 0.0,
+    low_price REAL NOT NULL DEFAULT 0.0,
+    volume REAL NOT NULL DEFAULT 0.0,
+    volume_weighted_price REAL DEFAULT 0.0,
+    stock_symbol TEXT NOT NULL,
+    timeframe TEXT NOT NULL,
+    bar_trend TEXT NOT NULL,
+    buy_or_sell INTEGER NOT NULL,
+    next_period_price REAL NOT NULL,
+    next_period_trend TEXT NOT NULL,
+    next_period_unix_timestamp INTEGER NOT NULL,
+    next_period_event_datetime TEXT NOT NULL,
+    previous_period_trend TEXT NOT NULL DEFAULT 'Bullish',
+    adjustment_mode TEXT NOT NULL DEFAULT 'raw',
+    hundred_day_sma REAL,
+    hundred_day_ema REAL,
+    fifty_day_sma REAL,
+    fifty_day_ema REAL,
+    twenty_day_sma REAL,
+    twenty_day_ema REAL,
+    nine_day_sma REAL,
+    nine_day_ema REAL,
+    hundred_day_high REAL,
+    hundred_day_low REAL,
+    fifty_day_high REAL,
+    fifty_day_low REAL,
+    ten_day_high REAL,
+    ten_day_low REAL,
+    fourteen_day_rsi REAL,
+    top_bollinger_band REAL,
+    middle_bollinger_band REAL,
+    bottom_bollinger_band REAL,
+    macd_signal REAL,
+    FOREIGN KEY (stock_symbol) REFERENCES symbols (ticker) ON UPDATE CASCADE
+);
+INSERT INTO daily_stock_bars_with_symbols (
+    id, event_datetime, event_unix_timestamp, open_price, close_price, high_price, low_price,
+    volume, volume_weighted_price, stock_symbol, timeframe, bar_trend, buy_or_sell,
+    next_period_price, next_period_trend, next_period_unix_timestamp,
+    next_period_event_datetime, previous_period_trend, adjustment_mode, hundred_day_sma,
+    hundred_day_ema, fifty_day_sma, fifty_day_ema, twenty_day_sma, twenty_day_ema, nine_day_sma,
+    nine_day_ema, hundred_day_high, hundred_day_low, fifty_day_high, fifty_day_low,
+    ten_day_high, ten_day_low, fourteen_day_rsi, top_bollinger_band, middle_bollinger_band,
+    bottom_bollinger_band, macd_signal
+)
+SELECT
+    id, event_datetime, event_unix_timestamp, open_price, close_price, high_price, low_price,
+    volume, volume_weighted_price, stock_symbol, timeframe, bar_trend, buy_or_sell,
+    next_period_price, next_period_trend, next_period_unix_timestamp,
+    next_period_event_datetime, previous_period_trend, adjustment_mode, hundred_day_sma,
+    hundred_day_ema, fifty_day_sma, fifty_day_ema, twenty_day_sma, twenty_day_ema, nine_day_sma,
+    nine_day_ema, hundred_day_high, hundred_day_low, fifty_day_high, fifty_day_low,
+    ten_day_high, ten_day_low, fourteen_day_rsi, top_bollinger_band, middle_bollinger_band,
+    bottom_bollinger_band, macd_signal
+FROM daily_stock_bars;
+DROP TABLE daily_stock_bars;
+ALTER TABLE daily_stock_bars_with_symbols RENAME TO daily_stock_bars;
+CREATE UNIQUE INDEX IF NOT EXISTS daily_stock_bars_symbol_timeframe_timestamp
+ON daily_stock_bars (stock_symbol, timeframe, event_unix_timestamp);
+
+CREATE TABLE hourly_stock_bars_with_symbols (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_datetime TEXT NOT NULL,
+    event_unix_timestamp INTEGER NOT NULL,
+    open_price REAL NOT NULL DEFAULT 0.0,
+    close_price REAL NOT NULL DEFAULT 0.0,
+    high_price REAL NOT NULL DEFAULT 0.0,
+    low_price REAL NOT NULL DEFAULT 0.0,
+    volume REAL NOT NULL DEFAULT 0.0,
+    volume_weighted_price REAL DEFAULT 0.0,
+    stock_symbol TEXT NOT NULL,
+    timeframe TEXT NOT NULL,
+    bar_trend TEXT NOT NULL,
+    buy_or_sell INTEGER NOT NULL,
+    next_period_price REAL NOT NULL,
+    next_period_trend TEXT NOT NULL,
+    next_period_unix_timestamp INTEGER NOT NULL,
+    next_period_event_datetime TEXT NOT NULL,
+    previous_period_trend TEXT NOT NULL DEFAULT 'Bullish',
+    adjustment_mode TEXT NOT NULL DEFAULT 'raw',
+    five_period_sma REAL,
+    eight_period_sma REAL,
+    thirteen_period_sma REAL,
+    nine_period_rsi REAL,
+    top_bollinger_band REAL,
+    middle_bollinger_band REAL,
+    bottom_bollinger_band REAL,
+    twenty_period_high REAL,
+    twenty_period_low REAL,
+    eight_period_high REAL,
+    eight_period_low REAL,
+    five_period_high REAL,
+    five_period_low REAL,
+    FOREIGN KEY (stock_symbol) REFERENCES symbols (ticker) ON UPDATE CASCADE
+);
+INSERT INTO hourly_stock_bars_with_symbols (
+    id, event_datetime, event_unix_timestamp, open_price, close_price, high_price, low_price,
+    volume, volume_weighted_price, stock_symbol, timeframe, bar_trend, buy_or_sell,
+    next_period_price, next_period_trend, next_period_unix_timestamp,
+    next_period_event_datetime, previous_period_trend, adjustment_mode, five_period_sma,
+    eight_period_sma, thirteen_period_sma, nine_period_rsi, top_bollinger_band,
+    middle_bollinger_band, bottom_bollinger_band, twenty_period_high, twenty_period_low,
+    eight_period_high, eight_period_low, five_period_high, five_period_low
+)
+SELECT
+    id, event_datetime, event_unix_timestamp, open_price, close_price, high_price, low_price,
+    volume, volume_weighted_price, stock_symbol, timeframe, bar_trend, buy_or_sell,
+    next_period_price, next_period_trend, next_period_unix_timestamp,
+    next_period_event_datetime, previous_period_trend, adjustment_mode, five_period_sma,
+    eight_period_sma, thirteen_period_sma, nine_period_rsi, top_bollinger_band,
+    middle_bollinger_band, bottom_bollinger_band, twenty_period_high, twenty_period_low,
+    eight_period_high, eight_period_low, five_period_high, five_period_low
+FROM hourly_stock_bars;
+DROP TABLE hourly_stock_bars;
+ALTER TABLE hourly_stock_bars_with_symbols RENAME TO hourly_stock_bars;
+CREATE UNIQUE INDEX IF NOT EXISTS hourly_stock_bars_symbol_timeframe_timestamp
+ON hourly_stock_bars (stock_symbol, timeframe, event_unix_timestamp);
+
+CREATE TABLE fifteen_minute_stock_bars_with_symbols (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_datetime TEXT NOT NULL,
+    event_unix_timestamp INTEGER NOT NULL,
+    open_price REAL NOT NULL DEFAULT 0.0,
+    close_price REAL NOT NULL DEFAULT 0.0,
+    high_price REAL NOT NULL DEFAULT 0.0,
+    low_price REAL NOT NULL DEFAULT 0.0,
+    volume REAL NOT NULL DEFAULT 0.0,
+    volume_weighted_price REAL DEFAULT 0.0,
+    stock_symbol TEXT NOT NULL,
+    timeframe TEXT NOT NULL,
+    bar_trend TEXT NOT NULL,
+    buy_or_sell INTEGER NOT NULL,
+    next_period_price REAL NOT NULL,
+    next_period_trend TEXT NOT NULL,
+    next_period_unix_timestamp INTEGER NOT NULL,
+    next_period_event_datetime TEXT NOT NULL,
+    previous_period_trend TEXT NOT NULL DEFAULT 'Bullish',
+    adjustment_mode TEXT NOT NULL DEFAULT 'raw',
+    five_period_sma REAL,
+    eight_period_sma REAL,
+    thirteen_period_sma REAL,
+    twenty_period_ema REAL,
+    nine_period_rsi REAL,
+    top_bollinger_band REAL,
+    middle_bollinger_band REAL,
+    bottom_bollinger_band REAL,
+    twenty_period_high REAL,
+    twenty_period_low REAL,
+    eight_period_high REAL,
+    eight_period_low REAL,
+    five_period_high REAL,
+    five_period_low REAL,
+    FOREIGN KEY (stock_symbol) REFERENCES symbols (ticker) ON UPDATE CASCADE
+);
+INSERT INTO fifteen_minute_stock_bars_with_symbols (
+    id, event_datetime, event_unix_timestamp, open_price, close_price, high_price, low_price,
+    volume, volume_weighted_price, stock_symbol, timeframe, bar_trend, buy_or_sell,
+    next_period_price, next_period_trend, next_period_unix_timestamp,
+    next_period_event_datetime, previous_period_trend, adjustment_mode, five_period_sma,
+    eight_period_sma, thirteen_period_sma, twenty_period_ema, nine_period_rsi,
+    top_bollinger_band, middle_bollinger_band, bottom_bollinger_band, twenty_period_high,
+    twenty_period_low, eight_period_high, eight_period_low, five_period_high, five_period_low
+)
+SELECT
+    id, event_datetime, event_unix_timestamp, open_price, close_price, high_price, low_price,
+    volume, volume_weighted_price, stock_symbol, timeframe, bar_trend, buy_or_sell,
+    next_period_price, next_period_trend, next_period_unix_timestamp,
+    next_period_event_datetime, previous_period_trend, adjustment_mode, five_period_sma,
+    eight_period_sma, thirteen_period_sma, twenty_period_ema, nine_period_rsi,
+    top_bollinger_band, middle_bollinger_band, bottom_bollinger_band, twenty_period_high,
+    twenty_period_low, eight_period_high, eight_period_low, five_period_high, five_period_low
+FROM fifteen_minute_stock_bars;
+DROP TABLE fifteen_minute_stock_bars;
+ALTER TABLE fifteen_minute_stock_bars_with_symbols RENAME TO fifteen_minute_stock_bars;
+CREATE UNIQUE INDEX IF NOT EXISTS fifteen_minute_stock_bars_symbol_timeframe_timestamp
+ON fifteen_minute_stock_bars (stock_symbol, timeframe, event_unix_timestamp);
+
+-- Bar dates of the existing bars, by UTC day
+UPDATE symbols SET
+    first_bar_date = bar_dates.first_bar_date,
+    last_bar_date = bar_dates.last_bar_date
+FROM (
+    SELECT stock_symbol, MIN(date(event_datetime)) AS first_bar_date,
+        MAX(date(event_datetime)) AS last_bar_date
+    FROM (
+        SELECT stock_symbol, event_datetime FROM monthly_stock_bars
+        UNION ALL SELECT stock_symbol, event_datetime FROM weekly_stock_bars
+        UNION ALL SELECT stock_symbol, event_datetime FROM daily_stock_bars
+        UNION ALL SELECT stock_symbol, event_datetime FROM hourly_stock_bars
+        UNION ALL SELECT stock_symbol, event_datetime FROM fifteen_minute_stock_bars
+    )
+    GROUP BY stock_symbol
+) AS bar_dates
+WHERE bar_dates.stock_symbol = symbols.ticker;
@@ -0,0 +1 @@
+DROP TABLE sector_relative_features;
@@ -0,0 +1,18 @@
+-- Add migration script here
+-- Features of a bar relative to its benchmark ETF, SPY and its sector peers.
+-- Keyed like the bar tables, since weekly and daily bars can share a timestamp.
+CREATE TABLE IF NOT EXISTS sector_relative_features (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    stock_symbol TEXT NOT NULL REFERENCES symbols (ticker) ON UPDATE CASCADE,
+    timeframe TEXT NOT NULL,
+    event_unix_timestamp INTEGER NOT NULL,
+    benchmark_etf TEXT,
+    relative_strength_vs_sector REAL,
+    relative_strength_vs_market REAL,
+    beta_to_benchmark REAL,
+    correlation_to_benchmark REAL,
+    sector_percentile_rank REAL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS sector_relative_features_symbol_timeframe_timestamp
+ON sector_relative_features (stock_symbol, timeframe, event_unix_timestamp);
@@ -0,0 +1 @@
+DROP TABLE daily_cross_sectional_features;
@@ -0,0 +1,28 @@
+-- Add migration script here
+-- Where each daily bar stands among the whole universe on its day. Ranks are
+-- 1 for the highest value, percentiles the share of other symbols below it
+-- and z-scores the distance from the day's mean in standard deviations.
+CREATE TABLE IF NOT EXISTS daily_cross_sectional_features (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    stock_symbol TEXT NOT NULL REFERENCES symbols (ticker) ON UPDATE CASCADE,
+    event_unix_timestamp INTEGER NOT NULL,
+    universe_size INTEGER NOT NULL,
+    rsi_rank INTEGER,
+    rsi_percentile REAL,
+    rsi_zscore REAL,
+    twenty_day_return_rank INTEGER,
+    twenty_day_return_percentile REAL,
+    twenty_day_return_zscore REAL,
+    volume_rank INTEGER,
+    volume_percentile REAL,
+    volume_zscore REAL,
+    fifty_day_sma_distance_rank INTEGER,
+    fifty_day_sma_distance_percentile REAL,
+    fifty_day_sma_distance_zscore REAL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS daily_cross_sectional_features_symbol_timestamp
+ON daily_cross_sectional_features (stock_symbol, event_unix_timestamp);
+
+CREATE INDEX IF NOT EXISTS daily_cross_sectional_features_timestamp
+ON daily_cross_sectional_features (event_unix_timestamp);
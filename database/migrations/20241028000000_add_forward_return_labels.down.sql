@@ -0,0 +1 @@
+DROP TABLE forward_return_labels;
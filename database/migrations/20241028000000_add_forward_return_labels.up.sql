@@ -0,0 +1,19 @@
+-- Add migration script here
+-- Labels looking N bars ahead of a bar, one row per horizon. Returns, run-ups
+-- and drawdowns are measured from the bar's close; the direction is flat
+-- while the return stays within the dead zone.
+CREATE TABLE IF NOT EXISTS forward_return_labels (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    stock_symbol TEXT NOT NULL REFERENCES symbols (ticker) ON UPDATE CASCADE,
+    timeframe TEXT NOT NULL,
+    event_unix_timestamp INTEGER NOT NULL,
+    horizon_bars INTEGER NOT NULL CHECK (horizon_bars > 0),
+    forward_return REAL NOT NULL,
+    max_run_up REAL NOT NULL,
+    max_drawdown REAL NOT NULL,
+    dead_zone REAL NOT NULL,
+    direction TEXT NOT NULL CHECK (direction IN ('up', 'down', 'flat'))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS forward_return_labels_symbol_timeframe_timestamp_horizon
+ON forward_return_labels (stock_symbol, timeframe, event_unix_timestamp, horizon_bars);
@@ -0,0 +1,5 @@
+ALTER TABLE monthly_stock_bars DROP COLUMN provenance;
+ALTER TABLE weekly_stock_bars DROP COLUMN provenance;
+ALTER TABLE daily_stock_bars DROP COLUMN provenance;
+ALTER TABLE hourly_stock_bars DROP COLUMN provenance;
+ALTER TABLE fifteen_minute_stock_bars DROP COLUMN provenance;
@@ -0,0 +1,6 @@
+-- Bars stored so far are recorded as fetched, including any that were resampled
+ALTER TABLE monthly_stock_bars ADD COLUMN provenance TEXT NOT NULL DEFAULT 'fetched';
+ALTER TABLE weekly_stock_bars ADD COLUMN provenance TEXT NOT NULL DEFAULT 'fetched';
+ALTER TABLE daily_stock_bars ADD COLUMN provenance TEXT NOT NULL DEFAULT 'fetched';
+ALTER TABLE hourly_stock_bars ADD COLUMN provenance TEXT NOT NULL DEFAULT 'fetched';
+ALTER TABLE fifteen_minute_stock_bars ADD COLUMN provenance TEXT NOT NULL DEFAULT 'fetched';
@@ -1,10 +1,18 @@
 #![allow(async_fn_in_trait)]
+use std::collections::HashMap;
+
 use anyhow::Result;
 use sqlx::{migrate::MigrateDatabase, SqlitePool};
 
 mod market_data;
 pub use market_data::*;
 
+mod migrations;
+pub use migrations::*;
+
+#[cfg(test)]
+mod test_support;
+
 pub struct SqliteDb {
     pub uri: String,
     pub pool: SqlitePool,
@@ -21,20 +29,28 @@ impl SqliteDb {
         })
     }
 
+    /// Creates the database if it does not exist yet and applies any pending
+    /// migrations.
     pub async fn create_new(uri: &str) -> Result<Self> {
         let does_exist = sqlx::Sqlite::database_exists(uri).await.unwrap_or(false);
 
-        if does_exist {
+        let connection = if does_exist {
             println!("Database already exists, connecting...");
-            let connection = Self::connect(uri).await?;
-            return Ok(connection);
-        }
+            Self::connect(uri).await?
+        } else {
+            println!("Creating database at {}", uri);
+            sqlx::Sqlite::create_database(uri).await?;
 
-        println!("Creating database at {}", uri);
-        sqlx::Sqlite::create_database(uri).await?;
+            println!("Database created, connecting...");
+            Self::connect(uri).await?
+        };
 
-        println!("Database created, connecting...");
-        let connection = Self::connect(uri).await?;
+        for migration in connection.migrate().await? {
+            println!(
+                "Applied migration {} {}",
+                migration.version, migration.description
+            );
+        }
         Ok(connection)
     }
 
@@ -52,4 +68,26 @@ impl SqliteDb {
         Self::create_new(uri).await?;
         Ok(())
     }
+
+    /// Latest stored `event_unix_timestamp` for every symbol in a bar table.
+    pub async fn latest_event_unix_timestamps(&self, table: &str) -> Result<HashMap<String, i64>> {
+        let rows: Vec<(String, i64)> = sqlx::query_as(&format!(
+            "SELECT stock_symbol, MAX(event_unix_timestamp) FROM {} GROUP BY stock_symbol",
+            table
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Column names of a table, in declaration order.
+    pub async fn table_columns(&self, table: &str) -> Result<Vec<String>> {
+        let columns =
+            sqlx::query_scalar(&format!("SELECT name FROM pragma_table_info('{}')", table))
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(columns)
+    }
 }
@@ -0,0 +1,27 @@
+/// Upper bound on bind parameters in a single statement for the SQLite bundled
+/// with sqlx (`SQLITE_MAX_VARIABLE_NUMBER`, 32766 since SQLite 3.32).
+const SQLITE_MAX_BIND_PARAMETERS: usize = 32766;
+
+/// How many rows of a table with `column_count` columns fit in one multi-row
+/// `INSERT ... VALUES` statement.
+pub(crate) fn rows_per_statement(column_count: usize) -> usize {
+    (SQLITE_MAX_BIND_PARAMETERS / column_count).max(1)
+}
+
+pub(crate) fn insert_prefix(table: &str, columns: &[&str]) -> String {
+    format!("INSERT INTO {} ({}) ", table, columns.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_per_statement_stays_under_bind_limit() {
+        for column_count in [23, 29, 30, 36] {
+            let rows = rows_per_statement(column_count);
+            assert!(rows * column_count <= SQLITE_MAX_BIND_PARAMETERS);
+            assert!((rows + 1) * column_count > SQLITE_MAX_BIND_PARAMETERS);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::fmt;
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::SqliteDb;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum CorporateActionType {
+    Split,
+    Dividend,
+}
+
+/// A split or cash dividend taking effect on `ex_date`.
+#[derive(Debug, Clone, PartialEq, Deserialize, sqlx::FromRow)]
+pub struct CorporateAction {
+    #[serde(alias = "symbol")]
+    pub stock_symbol: String,
+    pub action_type: CorporateActionType,
+    pub ex_date: NaiveDate,
+    /// Shares after the split for every share before, e.g. 4.0 for a 4-for-1
+    /// split and 0.1 for a 1-for-10 reverse split.
+    pub split_ratio: Option<f64>,
+    /// Cash paid per share.
+    pub cash_amount: Option<f64>,
+}
+
+/// How the prices of a stored bar were adjusted for corporate actions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AdjustmentMode {
+    /// Prices as traded.
+    #[default]
+    Raw,
+    /// Back-adjusted for splits.
+    Split,
+    /// Back-adjusted for splits and reinvested cash dividends.
+    TotalReturn,
+}
+
+impl AdjustmentMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AdjustmentMode::Raw => "raw",
+            AdjustmentMode::Split => "split",
+            AdjustmentMode::TotalReturn => "total_return",
+        }
+    }
+}
+
+impl fmt::Display for AdjustmentMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl SqliteDb {
+    /// Inserts the actions, replacing any stored action of the same symbol,
+    /// type and ex-date. Returns how many rows were written.
+    pub async fn upsert_corporate_actions(&self, actions: &[CorporateAction]) -> Result<u64> {
+        let mut transaction = self.pool.begin().await?;
+        let mut rows = 0;
+        for action in actions {
+            let result = sqlx::query(
+                "INSERT INTO corporate_actions (stock_symbol, action_type, ex_date, split_ratio, cash_amount)
+                VALUES (?, ?, ?, ?, ?)
+                ON CONFLICT (stock_symbol, action_type, ex_date)
+                DO UPDATE SET split_ratio = excluded.split_ratio, cash_amount = excluded.cash_amount",
+            )
+            .bind(&action.stock_symbol)
+            .bind(action.action_type)
+            .bind(action.ex_date)
+            .bind(action.split_ratio)
+            .bind(action.cash_amount)
+            .execute(&mut *transaction)
+            .await?;
+            rows += result.rows_affected();
+        }
+        transaction.commit().await?;
+        Ok(rows)
+    }
+
+    /// Actions of a symbol, oldest first.
+    pub async fn corporate_actions(&self, stock_symbol: &str) -> Result<Vec<CorporateAction>> {
+        let actions = sqlx::query_as(
+            "SELECT stock_symbol, action_type, ex_date, split_ratio, cash_amount
+            FROM corporate_actions
+            WHERE stock_symbol = ?
+            ORDER BY ex_date",
+        )
+        .bind(stock_symbol)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(actions)
+    }
+}
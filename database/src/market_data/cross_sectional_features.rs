@@ -0,0 +1,157 @@
+use anyhow::Result;
+use sqlx::{sqlite::SqliteRow, FromRow, QueryBuilder, Row};
+
+use super::batch::rows_per_statement;
+use crate::SqliteDb;
+
+const CROSS_SECTIONAL_FEATURE_COLUMNS: [&str; 15] = [
+    "stock_symbol",
+    "event_unix_timestamp",
+    "universe_size",
+    "rsi_rank",
+    "rsi_percentile",
+    "rsi_zscore",
+    "twenty_day_return_rank",
+    "twenty_day_return_percentile",
+    "twenty_day_return_zscore",
+    "volume_rank",
+    "volume_percentile",
+    "volume_zscore",
+    "fifty_day_sma_distance_rank",
+    "fifty_day_sma_distance_percentile",
+    "fifty_day_sma_distance_zscore",
+];
+
+/// Where a value stands among those of every symbol on the same day.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CrossSectionalScore {
+    /// 1 for the highest value. Ties share the better rank.
+    pub rank: Option<i64>,
+    /// Share of the other symbols with a lower value, from 0.0 to 1.0.
+    pub percentile: Option<f32>,
+    /// Distance from the day's mean in standard deviations.
+    pub zscore: Option<f32>,
+}
+
+/// Cross-sectional features of one daily bar, stored in
+/// `daily_cross_sectional_features`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrossSectionalFeatures {
+    pub stock_symbol: String,
+    pub event_unix_timestamp: i64,
+    /// Symbols with a bar on the day.
+    pub universe_size: i64,
+    /// Of the fourteen-day RSI.
+    pub rsi: CrossSectionalScore,
+    /// Of the close over the close twenty bars earlier.
+    pub twenty_day_return: CrossSectionalScore,
+    pub volume: CrossSectionalScore,
+    /// Of the close over the fifty-day SMA, minus one.
+    pub fifty_day_sma_distance: CrossSectionalScore,
+}
+
+impl CrossSectionalFeatures {
+    fn scores(&self) -> [&CrossSectionalScore; 4] {
+        [
+            &self.rsi,
+            &self.twenty_day_return,
+            &self.volume,
+            &self.fifty_day_sma_distance,
+        ]
+    }
+}
+
+impl<'r> FromRow<'r, SqliteRow> for CrossSectionalFeatures {
+    fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+        let score = |prefix: &str| -> Result<CrossSectionalScore, sqlx::Error> {
+            Ok(CrossSectionalScore {
+                rank: row.try_get(format!("{}_rank", prefix).as_str())?,
+                percentile: row.try_get(format!("{}_percentile", prefix).as_str())?,
+                zscore: row.try_get(format!("{}_zscore", prefix).as_str())?,
+            })
+        };
+        Ok(Self {
+            stock_symbol: row.try_get("stock_symbol")?,
+            event_unix_timestamp: row.try_get("event_unix_timestamp")?,
+            universe_size: row.try_get("universe_size")?,
+            rsi: score("rsi")?,
+            twenty_day_return: score("twenty_day_return")?,
+            volume: score("volume")?,
+            fifty_day_sma_distance: score("fifty_day_sma_distance")?,
+        })
+    }
+}
+
+impl SqliteDb {
+    /// Inserts the rows, replacing stored rows of the same bar. Returns how
+    /// many rows were written.
+    pub async fn upsert_cross_sectional_features(
+        &self,
+        rows: &[CrossSectionalFeatures],
+    ) -> Result<u64> {
+        let updates = CROSS_SECTIONAL_FEATURE_COLUMNS[2..]
+            .iter()
+            .map(|column| format!("{0} = excluded.{0}", column))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut written = 0;
+        let mut transaction = self.pool.begin().await?;
+        for chunk in rows.chunks(rows_per_statement(CROSS_SECTIONAL_FEATURE_COLUMNS.len())) {
+            let mut query_builder = QueryBuilder::new(format!(
+                "INSERT INTO daily_cross_sectional_features ({}) ",
+                CROSS_SECTIONAL_FEATURE_COLUMNS.join(", ")
+            ));
+            query_builder.push_values(chunk, |mut row, features| {
+                row.push_bind(&features.stock_symbol)
+                    .push_bind(features.event_unix_timestamp)
+                    .push_bind(features.universe_size);
+                for score in features.scores() {
+                    row.push_bind(score.rank)
+                        .push_bind(score.percentile)
+                        .push_bind(score.zscore);
+                }
+            });
+            query_builder.push(format!(
+                " ON CONFLICT (stock_symbol, event_unix_timestamp) DO UPDATE SET {}",
+                updates
+            ));
+            written += query_builder
+                .build()
+                .execute(&mut *transaction)
+                .await?
+                .rows_affected();
+        }
+        transaction.commit().await?;
+        Ok(written)
+    }
+
+    /// Stored rows of a symbol, oldest first.
+    pub async fn cross_sectional_features(
+        &self,
+        stock_symbol: &str,
+    ) -> Result<Vec<CrossSectionalFeatures>> {
+        let rows = sqlx::query_as(&format!(
+            "SELECT {} FROM daily_cross_sectional_features
+            WHERE stock_symbol = ?
+            ORDER BY event_unix_timestamp",
+            CROSS_SECTIONAL_FEATURE_COLUMNS.join(", ")
+        ))
+        .bind(stock_symbol)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Latest `event_unix_timestamp` with stored cross-sectional features.
+    pub async fn latest_cross_sectional_features_timestamp(&self) -> Result<Option<i64>> {
+        let latest = sqlx::query_scalar(
+            "SELECT MAX(event_unix_timestamp) FROM daily_cross_sectional_features",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(latest)
+    }
+}
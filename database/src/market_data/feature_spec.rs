@@ -0,0 +1,251 @@
+use super::timeframe::CORE_COLUMNS;
+
+/// Bar field an indicator is computed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceField {
+    Open,
+    High,
+    Low,
+    Close,
+    Volume,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BollingerBand {
+    Top,
+    Middle,
+    Bottom,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Indicator {
+    Sma {
+        period: usize,
+    },
+    Ema {
+        period: usize,
+    },
+    Rsi {
+        period: usize,
+    },
+    High {
+        period: usize,
+    },
+    Low {
+        period: usize,
+    },
+    Bollinger {
+        band: BollingerBand,
+        period: usize,
+        multiplier: f32,
+    },
+    /// Signal line of a 12/26/9 MACD.
+    MacdSignal,
+}
+
+/// Bars needed by `tindi` to compute a 12/26/9 MACD signal.
+pub const MACD_PERIOD: usize = 35;
+
+impl Indicator {
+    /// Number of previous bars the indicator looks at.
+    pub fn period(&self) -> usize {
+        match self {
+            Indicator::Sma { period }
+            | Indicator::Ema { period }
+            | Indicator::Rsi { period }
+            | Indicator::High { period }
+            | Indicator::Low { period }
+            | Indicator::Bollinger { period, .. } => *period,
+            Indicator::MacdSignal => MACD_PERIOD,
+        }
+    }
+}
+
+/// One indicator column of a bar table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feature {
+    pub column: String,
+    pub indicator: Indicator,
+    pub source: PriceField,
+}
+
+/// The indicator columns of a bar table and how each one is computed.
+///
+/// ```ignore
+/// let spec = FeatureSpec::new()
+///     .sma("two_hundred_day_sma", PriceField::Close, 200)
+///     .rsi("fourteen_day_rsi", PriceField::Close, 14)
+///     .bollinger_bands(
+///         ["top_bollinger_band", "middle_bollinger_band", "bottom_bollinger_band"],
+///         PriceField::Close,
+///         20,
+///         2.0,
+///     );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeatureSpec {
+    features: Vec<Feature>,
+}
+
+impl FeatureSpec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn feature(mut self, column: &str, indicator: Indicator, source: PriceField) -> Self {
+        self.features.push(Feature {
+            column: column.to_string(),
+            indicator,
+            source,
+        });
+        self
+    }
+
+    pub fn sma(self, column: &str, source: PriceField, period: usize) -> Self {
+        self.feature(column, Indicator::Sma { period }, source)
+    }
+
+    pub fn ema(self, column: &str, source: PriceField, period: usize) -> Self {
+        self.feature(column, Indicator::Ema { period }, source)
+    }
+
+    pub fn rsi(self, column: &str, source: PriceField, period: usize) -> Self {
+        self.feature(column, Indicator::Rsi { period }, source)
+    }
+
+    pub fn high(self, column: &str, source: PriceField, period: usize) -> Self {
+        self.feature(column, Indicator::High { period }, source)
+    }
+
+    pub fn low(self, column: &str, source: PriceField, period: usize) -> Self {
+        self.feature(column, Indicator::Low { period }, source)
+    }
+
+    /// Adds the top, middle and bottom band columns, in that order.
+    pub fn bollinger_bands(
+        self,
+        columns: [&str; 3],
+        source: PriceField,
+        period: usize,
+        multiplier: f32,
+    ) -> Self {
+        let [top, middle, bottom] = columns;
+        let band = |band| Indicator::Bollinger {
+            band,
+            period,
+            multiplier,
+        };
+        self.feature(top, band(BollingerBand::Top), source)
+            .feature(middle, band(BollingerBand::Middle), source)
+            .feature(bottom, band(BollingerBand::Bottom), source)
+    }
+
+    pub fn macd_signal(self, column: &str, source: PriceField) -> Self {
+        self.feature(column, Indicator::MacdSignal, source)
+    }
+
+    pub fn features(&self) -> &[Feature] {
+        &self.features
+    }
+
+    pub fn len(&self) -> usize {
+        self.features.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+
+    /// Feature column names, in the order feature values are computed and bound.
+    pub fn columns(&self) -> Vec<&str> {
+        self.features
+            .iter()
+            .map(|feature| feature.column.as_str())
+            .collect()
+    }
+
+    /// All insertable columns of the table: the core followed by the features.
+    pub fn table_columns(&self) -> Vec<&str> {
+        let mut columns = CORE_COLUMNS.to_vec();
+        columns.extend(self.columns());
+        columns
+    }
+
+    /// Longest look-back of any feature, in bars.
+    pub fn warm_up_bars(&self) -> usize {
+        self.features
+            .iter()
+            .map(|feature| feature.indicator.period())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// `CREATE TABLE` statement for a bar table holding this feature set. Feature
+    /// columns are nullable and hold NULL while their indicator is warming up.
+    pub fn create_table_sql(&self, table: &str) -> String {
+        let feature_columns: String = self
+            .features
+            .iter()
+            .map(|feature| format!(",\n    {} REAL", feature.column))
+            .collect();
+
+        format!(
+            "CREATE TABLE IF NOT EXISTS {table} (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_datetime TEXT NOT NULL,
+    event_unix_timestamp INTEGER NOT NULL,
+    open_price REAL NOT NULL DEFAULT 0.0,
+    close_price REAL NOT NULL DEFAULT 0.0,
+    high_price REAL NOT NULL DEFAULT 0.0,
+    low_price REAL NOT NULL DEFAULT 0.0,
+    volume REAL NOT NULL DEFAULT 0.0,
+    volume_weighted_price REAL DEFAULT 0.0,
+    stock_symbol TEXT NOT NULL,
+    timeframe TEXT NOT NULL,
+    bar_trend TEXT NOT NULL,
+    buy_or_sell INTEGER NOT NULL,
+    next_period_price REAL NOT NULL,
+    next_period_trend TEXT NOT NULL,
+    next_period_unix_timestamp INTEGER NOT NULL,
+    next_period_event_datetime TEXT NOT NULL,
+    previous_period_trend TEXT NOT NULL DEFAULT 'Bullish',
+    adjustment_mode TEXT NOT NULL DEFAULT 'raw',
+    provenance TEXT NOT NULL DEFAULT 'fetched'{feature_columns},
+    FOREIGN KEY (stock_symbol) REFERENCES symbols (ticker) ON UPDATE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS {table}_symbol_timeframe_timestamp
+ON {table} (stock_symbol, timeframe, event_unix_timestamp);
+"
+        )
+    }
+
+    /// `ALTER TABLE` statements adding the feature columns missing from
+    /// `existing_columns`, e.g. after a new indicator was added to the spec.
+    pub fn add_columns_sql(&self, table: &str, existing_columns: &[String]) -> Vec<String> {
+        self.features
+            .iter()
+            .filter(|feature| !existing_columns.contains(&feature.column))
+            .map(|feature| format!("ALTER TABLE {} ADD COLUMN {} REAL;", table, feature.column))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_columns_sql_only_adds_missing_features() {
+        let spec = FeatureSpec::new()
+            .sma("nine_day_sma", PriceField::Close, 9)
+            .sma("two_hundred_day_sma", PriceField::Close, 200);
+        let existing = vec!["id".to_string(), "nine_day_sma".to_string()];
+
+        assert_eq!(
+            spec.add_columns_sql("daily_stock_bars", &existing),
+            vec!["ALTER TABLE daily_stock_bars ADD COLUMN two_hundred_day_sma REAL;"]
+        );
+        assert_eq!(spec.warm_up_bars(), 200);
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use sqlx::QueryBuilder;
+
+use super::batch::rows_per_statement;
+use super::timeframe::Timeframe;
+use crate::SqliteDb;
+
+const LABEL_COLUMNS: [&str; 9] = [
+    "stock_symbol",
+    "timeframe",
+    "event_unix_timestamp",
+    "horizon_bars",
+    "forward_return",
+    "max_run_up",
+    "max_drawdown",
+    "dead_zone",
+    "direction",
+];
+
+/// Direction of a forward return, flat within the dead zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(rename_all = "lowercase")]
+pub enum LabelDirection {
+    Up,
+    Down,
+    Flat,
+}
+
+impl LabelDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LabelDirection::Up => "up",
+            LabelDirection::Down => "down",
+            LabelDirection::Flat => "flat",
+        }
+    }
+}
+
+/// Labels of one bar at one horizon, stored in `forward_return_labels`.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct ForwardReturnLabel {
+    pub stock_symbol: String,
+    pub event_unix_timestamp: i64,
+    /// Bars looked ahead.
+    pub horizon_bars: i64,
+    /// Close `horizon_bars` ahead over this bar's close, minus one.
+    pub forward_return: f32,
+    /// Highest high of the bars ahead over this bar's close, minus one.
+    pub max_run_up: f32,
+    /// Lowest low of the bars ahead over this bar's close, minus one.
+    pub max_drawdown: f32,
+    /// Largest absolute return still labelled flat.
+    pub dead_zone: f32,
+    pub direction: LabelDirection,
+}
+
+impl SqliteDb {
+    /// Inserts the labels, replacing stored labels of the same bar and
+    /// horizon. Returns how many rows were written.
+    pub async fn upsert_forward_return_labels(
+        &self,
+        timeframe: Timeframe,
+        labels: &[ForwardReturnLabel],
+    ) -> Result<u64> {
+        let timeframe = timeframe.alpaca_timeframe().to_string();
+        let updates = LABEL_COLUMNS[4..]
+            .iter()
+            .map(|column| format!("{0} = excluded.{0}", column))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut written = 0;
+        let mut transaction = self.pool.begin().await?;
+        for chunk in labels.chunks(rows_per_statement(LABEL_COLUMNS.len())) {
+            let mut query_builder = QueryBuilder::new(format!(
+                "INSERT INTO forward_return_labels ({}) ",
+                LABEL_COLUMNS.join(", ")
+            ));
+            query_builder.push_values(chunk, |mut row, label| {
+                row.push_bind(&label.stock_symbol)
+                    .push_bind(&timeframe)
+                    .push_bind(label.event_unix_timestamp)
+                    .push_bind(label.horizon_bars)
+                    .push_bind(label.forward_return)
+                    .push_bind(label.max_run_up)
+                    .push_bind(label.max_drawdown)
+                    .push_bind(label.dead_zone)
+                    .push_bind(label.direction);
+            });
+            query_builder.push(format!(
+                " ON CONFLICT (stock_symbol, timeframe, event_unix_timestamp, horizon_bars) DO UPDATE SET {}",
+                updates
+            ));
+            written += query_builder
+                .build()
+                .execute(&mut *transaction)
+                .await?
+                .rows_affected();
+        }
+        transaction.commit().await?;
+        Ok(written)
+    }
+
+    /// Stored labels of a symbol at one horizon, oldest first.
+    pub async fn forward_return_labels(
+        &self,
+        stock_symbol: &str,
+        timeframe: Timeframe,
+        horizon_bars: i64,
+    ) -> Result<Vec<ForwardReturnLabel>> {
+        let labels = sqlx::query_as(
+            "SELECT stock_symbol, event_unix_timestamp, horizon_bars, forward_return, max_run_up, max_drawdown, dead_zone, direction
+            FROM forward_return_labels
+            WHERE stock_symbol = ? AND timeframe = ? AND horizon_bars = ?
+            ORDER BY event_unix_timestamp",
+        )
+        .bind(stock_symbol)
+        .bind(timeframe.alpaca_timeframe().to_string())
+        .bind(horizon_bars)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(labels)
+    }
+
+    /// Latest labelled `event_unix_timestamp` of each symbol at each stored
+    /// horizon, keyed on `(stock_symbol, horizon_bars)`.
+    pub async fn latest_label_timestamps(
+        &self,
+        timeframe: Timeframe,
+    ) -> Result<HashMap<(String, i64), i64>> {
+        let latest: Vec<(String, i64, i64)> = sqlx::query_as(
+            "SELECT stock_symbol, horizon_bars, MAX(event_unix_timestamp) FROM forward_return_labels
+            WHERE timeframe = ?
+            GROUP BY stock_symbol, horizon_bars",
+        )
+        .bind(timeframe.alpaca_timeframe().to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(latest
+            .into_iter()
+            .map(|(stock_symbol, horizon_bars, latest)| ((stock_symbol, horizon_bars), latest))
+            .collect())
+    }
+}
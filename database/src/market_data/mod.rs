@@ -1,11 +1,37 @@
-mod monthly_stock_bars;
-pub use monthly_stock_bars::*;
+mod batch;
 
-mod daily_stock_bars;
-pub use daily_stock_bars::*;
+mod upsert;
+pub use upsert::*;
 
-mod hourly_stock_bars;
-pub use hourly_stock_bars::*;
+mod timeframe;
+pub use timeframe::*;
 
-mod fifteen_min_stock_bars;
-pub use fifteen_min_stock_bars::*;
+mod feature_spec;
+pub use feature_spec::*;
+
+mod stock_bars;
+pub use stock_bars::*;
+
+mod query;
+pub use query::*;
+
+mod record_batch;
+pub use record_batch::*;
+
+mod corporate_actions;
+pub use corporate_actions::*;
+
+mod watchlists;
+pub use watchlists::*;
+
+mod symbols;
+pub use symbols::*;
+
+mod relative_features;
+pub use relative_features::*;
+
+mod cross_sectional_features;
+pub use cross_sectional_features::*;
+
+mod labels;
+pub use labels::*;
@@ -0,0 +1,279 @@
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use futures_util::{stream::BoxStream, StreamExt, TryStreamExt};
+use sqlx::{
+    query::QueryAs,
+    sqlite::{Sqlite, SqliteArguments},
+};
+
+use super::{feature_spec::FeatureSpec, stock_bars::StockBarModel, timeframe::Timeframe};
+use crate::SqliteDb;
+
+/// Query over a bar table. Columns left out of a projection are filled with
+/// their default value.
+///
+/// ```ignore
+/// let bars = StockBarsQuery::new(Timeframe::Daily)
+///     .symbol("AAPL")
+///     .start(start)
+///     .columns(&["event_unix_timestamp", "close_price"])
+///     .fetch_all(&db)
+///     .await?;
+/// ```
+pub struct StockBarsQuery {
+    timeframe: Timeframe,
+    stock_symbols: Vec<String>,
+    sector: Option<String>,
+    start: Option<i64>,
+    end: Option<i64>,
+    columns: Option<Vec<String>>,
+    feature_spec: Option<FeatureSpec>,
+    newest_first: bool,
+    by_time: bool,
+    skip_warm_up: bool,
+    limit: Option<i64>,
+    sql: String,
+}
+
+impl StockBarsQuery {
+    pub fn new(timeframe: Timeframe) -> Self {
+        Self {
+            timeframe,
+            stock_symbols: Vec::new(),
+            sector: None,
+            start: None,
+            end: None,
+            columns: None,
+            feature_spec: None,
+            newest_first: false,
+            by_time: false,
+            skip_warm_up: false,
+            limit: None,
+            sql: String::new(),
+        }
+    }
+
+    pub fn timeframe(&self) -> Timeframe {
+        self.timeframe
+    }
+
+    /// Feature columns of the table, the timeframe's default spec unless
+    /// `features` was given.
+    pub fn feature_spec(&self) -> FeatureSpec {
+        self.feature_spec
+            .clone()
+            .unwrap_or_else(|| self.timeframe.feature_spec())
+    }
+
+    pub fn symbol(mut self, stock_symbol: &str) -> Self {
+        self.stock_symbols.push(stock_symbol.to_string());
+        self
+    }
+
+    pub fn symbols(mut self, stock_symbols: &[&str]) -> Self {
+        self.stock_symbols
+            .extend(stock_symbols.iter().map(|symbol| symbol.to_string()));
+        self
+    }
+
+    /// Only bars of tickers in this sector of the `symbols` table.
+    pub fn sector(mut self, sector: &str) -> Self {
+        self.sector = Some(sector.to_string());
+        self
+    }
+
+    /// Inclusive lower bound on the bar's event time.
+    pub fn start(mut self, start: DateTime<Utc>) -> Self {
+        self.start = Some(start.timestamp_millis());
+        self
+    }
+
+    /// Inclusive upper bound on the bar's event time.
+    pub fn end(mut self, end: DateTime<Utc>) -> Self {
+        self.end = Some(end.timestamp_millis());
+        self
+    }
+
+    pub fn columns(mut self, columns: &[&str]) -> Self {
+        self.columns = Some(columns.iter().map(|column| column.to_string()).collect());
+        self
+    }
+
+    /// Sets the feature spec the table was built with, when it has columns
+    /// beyond the timeframe's default spec.
+    pub fn features(mut self, spec: FeatureSpec) -> Self {
+        self.feature_spec = Some(spec);
+        self
+    }
+
+    pub fn newest_first(mut self) -> Self {
+        self.newest_first = true;
+        self
+    }
+
+    /// Orders bars by time across symbols instead of symbol by symbol.
+    pub fn by_time(mut self) -> Self {
+        self.by_time = true;
+        self
+    }
+
+    /// Leaves out bars where any feature of the timeframe's spec is still NULL
+    /// because its indicator was warming up.
+    pub fn skip_warm_up(mut self) -> Self {
+        self.skip_warm_up = true;
+        self
+    }
+
+    pub fn limit(mut self, limit: i64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub async fn fetch_all(mut self, db: &SqliteDb) -> Result<Vec<StockBarModel>> {
+        self.sql = self.build_sql()?;
+        let bars = self.bind_query().fetch_all(&db.pool).await?;
+        Ok(bars)
+    }
+
+    pub async fn fetch_optional(mut self, db: &SqliteDb) -> Result<Option<StockBarModel>> {
+        self.sql = self.build_sql()?;
+        let bar = self.bind_query().fetch_optional(&db.pool).await?;
+        Ok(bar)
+    }
+
+    /// Streams rows one at a time so a whole table can be walked without
+    /// loading it into memory.
+    pub fn stream<'a>(
+        &'a mut self,
+        db: &'a SqliteDb,
+    ) -> Result<BoxStream<'a, Result<StockBarModel>>> {
+        self.sql = self.build_sql()?;
+        let stream = self
+            .bind_query()
+            .fetch(&db.pool)
+            .map_err(anyhow::Error::from)
+            .boxed();
+        Ok(stream)
+    }
+
+    fn build_sql(&self) -> Result<String> {
+        let columns = match &self.columns {
+            Some(columns) => {
+                if let Some(column) = columns.iter().find(|column| !is_identifier(column)) {
+                    bail!("Invalid column name: {}", column);
+                }
+                columns.join(", ")
+            }
+            None => "*".to_string(),
+        };
+
+        let mut sql = format!(
+            "SELECT {} FROM {} WHERE 1 = 1",
+            columns,
+            self.timeframe.table()
+        );
+        if !self.stock_symbols.is_empty() {
+            let placeholders = vec!["?"; self.stock_symbols.len()].join(", ");
+            sql.push_str(&format!(" AND stock_symbol IN ({})", placeholders));
+        }
+        if self.sector.is_some() {
+            sql.push_str(" AND stock_symbol IN (SELECT ticker FROM symbols WHERE sector = ?)");
+        }
+        if self.start.is_some() {
+            sql.push_str(" AND event_unix_timestamp >= ?");
+        }
+        if self.end.is_some() {
+            sql.push_str(" AND event_unix_timestamp <= ?");
+        }
+        if self.skip_warm_up {
+            for column in self.feature_spec().columns() {
+                sql.push_str(&format!(" AND {} IS NOT NULL", column));
+            }
+        }
+
+        let direction = if self.newest_first { " DESC" } else { "" };
+        if self.by_time {
+            sql.push_str(&format!(
+                " ORDER BY event_unix_timestamp{}, stock_symbol",
+                direction
+            ));
+        } else {
+            sql.push_str(&format!(
+                " ORDER BY stock_symbol, event_unix_timestamp{}",
+                direction
+            ));
+        }
+
+        if let Some(limit) = self.limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        Ok(sql)
+    }
+
+    fn bind_query(&self) -> QueryAs<'_, Sqlite, StockBarModel, SqliteArguments<'_>> {
+        let mut query = sqlx::query_as(&self.sql);
+        for stock_symbol in &self.stock_symbols {
+            query = query.bind(stock_symbol);
+        }
+        if let Some(sector) = &self.sector {
+            query = query.bind(sector);
+        }
+        if let Some(start) = self.start {
+            query = query.bind(start);
+        }
+        if let Some(end) = self.end {
+            query = query.bind(end);
+        }
+        query
+    }
+}
+
+impl SqliteDb {
+    /// Bars for a symbol between `start` and `end` (inclusive), oldest first.
+    pub async fn get_bars(
+        &self,
+        stock_symbol: &str,
+        timeframe: Timeframe,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<StockBarModel>> {
+        StockBarsQuery::new(timeframe)
+            .symbol(stock_symbol)
+            .start(start)
+            .end(end)
+            .fetch_all(self)
+            .await
+    }
+
+    pub async fn latest_bar(
+        &self,
+        stock_symbol: &str,
+        timeframe: Timeframe,
+    ) -> Result<Option<StockBarModel>> {
+        StockBarsQuery::new(timeframe)
+            .symbol(stock_symbol)
+            .newest_first()
+            .limit(1)
+            .fetch_optional(self)
+            .await
+    }
+
+    pub async fn list_symbols(&self, timeframe: Timeframe) -> Result<Vec<String>> {
+        let symbols = sqlx::query_scalar(&format!(
+            "SELECT DISTINCT stock_symbol FROM {} ORDER BY stock_symbol",
+            timeframe.table()
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(symbols)
+    }
+}
+
+fn is_identifier(column: &str) -> bool {
+    !column.is_empty()
+        && column
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
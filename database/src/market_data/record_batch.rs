@@ -0,0 +1,192 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use arrow::{
+    array::{
+        ArrayRef, Float32Array, Int32Array, Int64Array, StringDictionaryBuilder,
+        TimestampMillisecondArray,
+    },
+    datatypes::{DataType, Field, Int32Type, Schema, SchemaRef, TimeUnit},
+    record_batch::RecordBatch,
+};
+
+use futures_util::{stream::BoxStream, StreamExt, TryStreamExt};
+
+use super::{feature_spec::FeatureSpec, query::StockBarsQuery, stock_bars::StockBarModel};
+use crate::SqliteDb;
+
+/// Rows per `RecordBatch` returned by `SqliteDb::bars_as_arrow`.
+pub const ARROW_BATCH_ROWS: usize = 8192;
+
+/// Arrow batches of a bar query. Every batch has `schema`, even when the query
+/// projects fewer columns.
+pub struct RecordBatchStream<'a> {
+    pub schema: SchemaRef,
+    pub batches: BoxStream<'a, Result<RecordBatch>>,
+}
+
+impl SqliteDb {
+    /// Streams the bars matching `query` as Arrow batches of up to
+    /// `ARROW_BATCH_ROWS` rows, with the schema of the query's feature spec.
+    ///
+    /// ```ignore
+    /// let mut query = StockBarsQuery::new(Timeframe::Daily).symbol("AAPL");
+    /// let mut bars = db.bars_as_arrow(&mut query)?;
+    /// while let Some(batch) = bars.batches.try_next().await? {
+    ///     println!("{} rows", batch.num_rows());
+    /// }
+    /// ```
+    pub fn bars_as_arrow<'a>(
+        &'a self,
+        query: &'a mut StockBarsQuery,
+    ) -> Result<RecordBatchStream<'a>> {
+        let spec = query.feature_spec();
+        let schema = stock_bars_schema(&spec);
+        let batches = query
+            .stream(self)?
+            .try_chunks(ARROW_BATCH_ROWS)
+            .map(move |chunk| {
+                let bars = chunk.map_err(|e| e.1)?;
+                stock_bars_record_batch(&spec, &bars)
+            })
+            .boxed();
+
+        Ok(RecordBatchStream { schema, batches })
+    }
+}
+
+/// Arrow schema of a bar table: the core columns followed by the spec's
+/// features. Datetimes are UTC millisecond timestamps and the repeated strings
+/// (symbol, timeframe, trends) are dictionary encoded.
+pub fn stock_bars_schema(spec: &FeatureSpec) -> SchemaRef {
+    let timestamp = || DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()));
+    let dictionary = || DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+
+    let mut fields = vec![
+        Field::new("id", DataType::Int64, false),
+        Field::new("event_datetime", timestamp(), false),
+        Field::new("event_unix_timestamp", DataType::Int64, false),
+        Field::new("open_price", DataType::Float32, false),
+        Field::new("close_price", DataType::Float32, false),
+        Field::new("high_price", DataType::Float32, false),
+        Field::new("low_price", DataType::Float32, false),
+        Field::new("volume", DataType::Float32, false),
+        Field::new("volume_weighted_price", DataType::Float32, false),
+        Field::new("stock_symbol", dictionary(), false),
+        Field::new("timeframe", dictionary(), false),
+        Field::new("bar_trend", dictionary(), false),
+        Field::new("buy_or_sell", DataType::Int32, false),
+        Field::new("next_period_price", DataType::Float32, false),
+        Field::new("next_period_trend", dictionary(), false),
+        Field::new("next_period_unix_timestamp", DataType::Int64, false),
+        Field::new("next_period_event_datetime", timestamp(), false),
+        Field::new("previous_period_trend", dictionary(), false),
+        Field::new("adjustment_mode", dictionary(), false),
+        Field::new("provenance", dictionary(), false),
+    ];
+    fields.extend(
+        spec.columns()
+            .into_iter()
+            .map(|column| Field::new(column, DataType::Float32, true)),
+    );
+
+    Arc::new(Schema::new(fields))
+}
+
+/// Converts bars into a batch with the schema of `stock_bars_schema(spec)`.
+pub fn stock_bars_record_batch(spec: &FeatureSpec, bars: &[StockBarModel]) -> Result<RecordBatch> {
+    let int64 = |value: fn(&StockBarModel) -> i64| -> ArrayRef {
+        Arc::new(bars.iter().map(value).collect::<Int64Array>())
+    };
+    let float32 = |value: fn(&StockBarModel) -> f32| -> ArrayRef {
+        Arc::new(bars.iter().map(value).collect::<Float32Array>())
+    };
+    let timestamp = |value: fn(&StockBarModel) -> i64| -> ArrayRef {
+        Arc::new(
+            bars.iter()
+                .map(value)
+                .collect::<TimestampMillisecondArray>()
+                .with_timezone("UTC"),
+        )
+    };
+    let dictionary = |value: fn(&StockBarModel) -> &str| -> ArrayRef {
+        let mut builder = StringDictionaryBuilder::<Int32Type>::new();
+        for bar in bars {
+            builder.append_value(value(bar));
+        }
+        Arc::new(builder.finish())
+    };
+
+    let buy_or_sell: ArrayRef = Arc::new(
+        bars.iter()
+            .map(|bar| bar.buy_or_sell)
+            .collect::<Int32Array>(),
+    );
+
+    let mut columns = vec![
+        int64(|bar| bar.id),
+        timestamp(|bar| bar.event_unix_timestamp),
+        int64(|bar| bar.event_unix_timestamp),
+        float32(|bar| bar.open_price),
+        float32(|bar| bar.close_price),
+        float32(|bar| bar.high_price),
+        float32(|bar| bar.low_price),
+        float32(|bar| bar.volume),
+        float32(|bar| bar.volume_weighted_price),
+        dictionary(|bar| bar.stock_symbol.as_str()),
+        dictionary(|bar| bar.timeframe.as_str()),
+        dictionary(|bar| bar.bar_trend.as_str()),
+        buy_or_sell,
+        float32(|bar| bar.next_period_price),
+        dictionary(|bar| bar.next_period_trend.as_str()),
+        int64(|bar| bar.next_period_unix_timestamp),
+        timestamp(|bar| bar.next_period_unix_timestamp),
+        dictionary(|bar| bar.previous_period_trend.as_str()),
+        dictionary(|bar| bar.adjustment_mode.as_str()),
+        dictionary(|bar| bar.provenance.as_str()),
+    ];
+    for column in spec.columns() {
+        columns.push(Arc::new(
+            bars.iter()
+                .map(|bar| bar.feature(column))
+                .collect::<Float32Array>(),
+        ));
+    }
+
+    Ok(RecordBatch::try_new(stock_bars_schema(spec), columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::Array;
+
+    use super::*;
+    use crate::Timeframe;
+
+    #[test]
+    fn test_record_batch_keeps_warm_up_features_null() {
+        let spec = Timeframe::Weekly.feature_spec();
+        let mut warm_up = StockBarModel {
+            stock_symbol: "AAPL".to_string(),
+            ..StockBarModel::default()
+        };
+        warm_up.features.insert("ten_week_sma".to_string(), None);
+        let mut warm = StockBarModel {
+            stock_symbol: "AAPL".to_string(),
+            ..StockBarModel::default()
+        };
+        warm.features.insert("ten_week_sma".to_string(), Some(1.5));
+
+        let batch = stock_bars_record_batch(&spec, &[warm_up, warm]).unwrap();
+
+        assert_eq!(batch.schema(), stock_bars_schema(&spec));
+        let sma = batch
+            .column_by_name("ten_week_sma")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Float32Array>()
+            .unwrap();
+        assert!(sma.is_null(0));
+        assert_eq!(sma.value(1), 1.5);
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use sqlx::QueryBuilder;
+
+use super::batch::rows_per_statement;
+use super::timeframe::Timeframe;
+use crate::SqliteDb;
+
+const RELATIVE_FEATURE_COLUMNS: [&str; 9] = [
+    "stock_symbol",
+    "timeframe",
+    "event_unix_timestamp",
+    "benchmark_etf",
+    "relative_strength_vs_sector",
+    "relative_strength_vs_market",
+    "beta_to_benchmark",
+    "correlation_to_benchmark",
+    "sector_percentile_rank",
+];
+
+/// Features of one bar measured against other symbols, stored in
+/// `sector_relative_features` next to the bar tables.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct RelativeFeatures {
+    pub stock_symbol: String,
+    pub event_unix_timestamp: i64,
+    /// The sector ETF, or SPY for symbols without one. Beta and correlation
+    /// are measured against it.
+    pub benchmark_etf: Option<String>,
+    /// Return over the lookback relative to the sector ETF's, as
+    /// `(1 + symbol) / (1 + benchmark) - 1`.
+    pub relative_strength_vs_sector: Option<f32>,
+    /// The same against SPY.
+    pub relative_strength_vs_market: Option<f32>,
+    pub beta_to_benchmark: Option<f32>,
+    pub correlation_to_benchmark: Option<f32>,
+    /// Share of the sector's stocks with a lower return over the lookback,
+    /// from 0.0 to 1.0.
+    pub sector_percentile_rank: Option<f32>,
+}
+
+impl SqliteDb {
+    /// Inserts the rows, replacing stored rows of the same bar. Returns how
+    /// many rows were written.
+    pub async fn upsert_relative_features(
+        &self,
+        timeframe: Timeframe,
+        rows: &[RelativeFeatures],
+    ) -> Result<u64> {
+        let timeframe = timeframe.alpaca_timeframe().to_string();
+        let updates = RELATIVE_FEATURE_COLUMNS[3..]
+            .iter()
+            .map(|column| format!("{0} = excluded.{0}", column))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut written = 0;
+        let mut transaction = self.pool.begin().await?;
+        for chunk in rows.chunks(rows_per_statement(RELATIVE_FEATURE_COLUMNS.len())) {
+            let mut query_builder = QueryBuilder::new(format!(
+                "INSERT INTO sector_relative_features ({}) ",
+                RELATIVE_FEATURE_COLUMNS.join(", ")
+            ));
+            query_builder.push_values(chunk, |mut row, features| {
+                row.push_bind(&features.stock_symbol)
+                    .push_bind(&timeframe)
+                    .push_bind(features.event_unix_timestamp)
+                    .push_bind(&features.benchmark_etf)
+                    .push_bind(features.relative_strength_vs_sector)
+                    .push_bind(features.relative_strength_vs_market)
+                    .push_bind(features.beta_to_benchmark)
+                    .push_bind(features.correlation_to_benchmark)
+                    .push_bind(features.sector_percentile_rank);
+            });
+            query_builder.push(format!(
+                " ON CONFLICT (stock_symbol, timeframe, event_unix_timestamp) DO UPDATE SET {}",
+                updates
+            ));
+            written += query_builder
+                .build()
+                .execute(&mut *transaction)
+                .await?
+                .rows_affected();
+        }
+        transaction.commit().await?;
+        Ok(written)
+    }
+
+    /// Stored rows of a symbol, oldest first.
+    pub async fn relative_features(
+        &self,
+        stock_symbol: &str,
+        timeframe: Timeframe,
+    ) -> Result<Vec<RelativeFeatures>> {
+        let rows = sqlx::query_as(&format!(
+            "SELECT {} FROM sector_relative_features
+            WHERE stock_symbol = ? AND timeframe = ?
+            ORDER BY event_unix_timestamp",
+            RELATIVE_FEATURE_COLUMNS.join(", ")
+        ))
+        .bind(stock_symbol)
+        .bind(timeframe.alpaca_timeframe().to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Latest `event_unix_timestamp` with stored relative features, by symbol.
+    pub async fn latest_relative_features_timestamps(
+        &self,
+        timeframe: Timeframe,
+    ) -> Result<HashMap<String, i64>> {
+        let rows: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT stock_symbol, MAX(event_unix_timestamp) FROM sector_relative_features
+            WHERE timeframe = ?
+            GROUP BY stock_symbol",
+        )
+        .bind(timeframe.alpaca_timeframe().to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+}
@@ -0,0 +1,461 @@
+use std::{collections::HashMap, fmt};
+
+use alpaca_api_client::{market_data::stocks::StockBar, TimeFrame, Trend};
+use anyhow::{bail, Result};
+use chrono::DateTime;
+use sqlx::{
+    query::Query,
+    query_builder::Separated,
+    sqlite::{Sqlite, SqliteArguments, SqliteRow},
+    Column, FromRow, QueryBuilder, Row, SqliteConnection,
+};
+
+use super::batch::{insert_prefix, rows_per_statement};
+use super::corporate_actions::AdjustmentMode;
+use super::feature_spec::FeatureSpec;
+use super::symbols::register_bar_symbols;
+use super::timeframe::Timeframe;
+use super::upsert::{
+    last_entry_per_key, max_stock_bar_id, upsert_clause, upsert_outcome, upsert_sql, UpsertOutcome,
+    UpsertReport,
+};
+use crate::SqliteDb;
+
+/// A stored bar. Columns missing from a projected query keep their default
+/// value, and every non-core column is collected into `features`, with `None`
+/// for indicators that were still warming up.
+#[derive(Debug, Clone, Default)]
+pub struct StockBarModel {
+    pub id: i64,
+    pub event_datetime: String,
+    pub event_unix_timestamp: i64,
+    pub open_price: f32,
+    pub close_price: f32,
+    pub high_price: f32,
+    pub low_price: f32,
+    pub volume: f32,
+    pub volume_weighted_price: f32,
+    pub stock_symbol: String,
+    pub timeframe: String,
+    pub bar_trend: String,
+    pub buy_or_sell: i32,
+    pub next_period_price: f32,
+    pub next_period_trend: String,
+    pub next_period_unix_timestamp: i64,
+    pub next_period_event_datetime: String,
+    pub previous_period_trend: String,
+    /// `AdjustmentMode` the prices were stored with.
+    pub adjustment_mode: String,
+    /// `BarProvenance` of the stored bar.
+    pub provenance: String,
+    pub features: HashMap<String, Option<f32>>,
+}
+
+impl StockBarModel {
+    pub fn feature(&self, column: &str) -> Option<f32> {
+        self.features.get(column).copied().flatten()
+    }
+}
+
+impl<'r> FromRow<'r, SqliteRow> for StockBarModel {
+    fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+        let mut model = StockBarModel::default();
+        for column in row.columns() {
+            let name = column.name();
+            match name {
+                "id" => model.id = row.try_get(name)?,
+                "event_datetime" => model.event_datetime = row.try_get(name)?,
+                "event_unix_timestamp" => model.event_unix_timestamp = row.try_get(name)?,
+                "open_price" => model.open_price = row.try_get(name)?,
+                "close_price" => model.close_price = row.try_get(name)?,
+                "high_price" => model.high_price = row.try_get(name)?,
+                "low_price" => model.low_price = row.try_get(name)?,
+                "volume" => model.volume = row.try_get(name)?,
+                "volume_weighted_price" => {
+                    model.volume_weighted_price =
+                        row.try_get::<Option<f32>, _>(name)?.unwrap_or_default()
+                }
+                "stock_symbol" => model.stock_symbol = row.try_get(name)?,
+                "timeframe" => model.timeframe = row.try_get(name)?,
+                "bar_trend" => model.bar_trend = row.try_get(name)?,
+                "buy_or_sell" => model.buy_or_sell = row.try_get(name)?,
+                "next_period_price" => model.next_period_price = row.try_get(name)?,
+                "next_period_trend" => model.next_period_trend = row.try_get(name)?,
+                "next_period_unix_timestamp" => {
+                    model.next_period_unix_timestamp = row.try_get(name)?
+                }
+                "next_period_event_datetime" => {
+                    model.next_period_event_datetime = row.try_get(name)?
+                }
+                "previous_period_trend" => model.previous_period_trend = row.try_get(name)?,
+                "adjustment_mode" => model.adjustment_mode = row.try_get(name)?,
+                "provenance" => model.provenance = row.try_get(name)?,
+                _ => {
+                    model
+                        .features
+                        .insert(name.to_string(), row.try_get::<Option<f32>, _>(name)?);
+                }
+            }
+        }
+        Ok(model)
+    }
+}
+
+/// Where a stored bar came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BarProvenance {
+    /// Fetched from Alpaca at the bar's timeframe, or replayed from recorded
+    /// Alpaca responses.
+    #[default]
+    Fetched,
+    /// Built from the stored fifteen-minute bars.
+    Resampled,
+    /// Imported from a vendor CSV export.
+    Imported,
+}
+
+impl BarProvenance {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BarProvenance::Fetched => "fetched",
+            BarProvenance::Resampled => "resampled",
+            BarProvenance::Imported => "imported",
+        }
+    }
+}
+
+impl fmt::Display for BarProvenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+pub struct StockBarModelEntry {
+    pub event_datetime: String,
+    pub event_unix_timestamp: i64,
+    pub open_price: f32,
+    pub close_price: f32,
+    pub high_price: f32,
+    pub low_price: f32,
+    pub volume: f32,
+    pub volume_weighted_price: f32,
+    pub stock_symbol: String,
+    pub timeframe: String,
+    pub bar_trend: String,
+    pub buy_or_sell: i32,
+    pub next_period_price: f32,
+    pub next_period_trend: String,
+    pub next_period_unix_timestamp: i64,
+    pub next_period_event_datetime: String,
+    pub previous_period_trend: String,
+    pub adjustment_mode: String,
+    pub provenance: String,
+    /// Values for the columns of the table's `FeatureSpec`, in the same order.
+    /// `None` while an indicator does not have enough previous bars.
+    pub features: Vec<Option<f32>>,
+}
+
+impl StockBarModelEntry {
+    pub fn new(
+        stock_bar: &StockBar,
+        previous_bar: Option<&StockBar>,
+        next_bar: &StockBar,
+        stock_symbol: &str,
+        timeframe: TimeFrame,
+        adjustment_mode: AdjustmentMode,
+        features: Vec<Option<f32>>,
+    ) -> Result<Self> {
+        let (event_datetime, event_unix_timestamp) = Self::format_timestamp(&stock_bar.t)?;
+        let (next_period_event_datetime, next_period_unix_timestamp) =
+            Self::format_timestamp(&next_bar.t)?;
+
+        let price_diff = next_bar.c - stock_bar.c;
+        let buy_or_sell = if price_diff > 0.0 { 1 } else { 0 };
+
+        // The first bar has no previous bar and is treated as bullish
+        let previous_period_trend = previous_bar.map_or(Trend::Bullish, Self::trend);
+
+        Ok(Self {
+            event_datetime,
+            event_unix_timestamp,
+            open_price: stock_bar.o,
+            close_price: stock_bar.c,
+            high_price: stock_bar.h,
+            low_price: stock_bar.l,
+            volume: stock_bar.v,
+            volume_weighted_price: stock_bar.vw,
+            stock_symbol: stock_symbol.to_string(),
+            timeframe: timeframe.to_string(),
+            bar_trend: Self::trend(stock_bar).to_string(),
+            buy_or_sell,
+            next_period_price: next_bar.c,
+            next_period_trend: Self::trend(next_bar).to_string(),
+            next_period_unix_timestamp,
+            next_period_event_datetime,
+            previous_period_trend: previous_period_trend.to_string(),
+            adjustment_mode: adjustment_mode.to_string(),
+            provenance: BarProvenance::Fetched.to_string(),
+            features,
+        })
+    }
+
+    fn trend(stock_bar: &StockBar) -> Trend {
+        if stock_bar.o > stock_bar.c {
+            Trend::Bearish
+        } else {
+            Trend::Bullish
+        }
+    }
+
+    fn format_timestamp(datetime: &str) -> Result<(String, i64)> {
+        let dt = DateTime::parse_from_rfc3339(datetime)?;
+
+        // UTC
+        let dt_utc = dt.with_timezone(&chrono::Utc);
+
+        // Format for SQLite
+        let dt_sqlite = dt_utc.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        // Unix timestamp
+        let unix_timestamp = dt_utc.timestamp_millis();
+
+        Ok((dt_sqlite, unix_timestamp))
+    }
+}
+
+pub trait BarRepository {
+    async fn insert_stock_bar(
+        &self,
+        timeframe: Timeframe,
+        spec: &FeatureSpec,
+        model_entry: &StockBarModelEntry,
+    ) -> Result<()>;
+    async fn insert_batch_of_stock_bars(
+        &self,
+        timeframe: Timeframe,
+        spec: &FeatureSpec,
+        model_entries: &[StockBarModelEntry],
+    ) -> Result<()>;
+    async fn upsert_stock_bar(
+        &self,
+        timeframe: Timeframe,
+        spec: &FeatureSpec,
+        model_entry: &StockBarModelEntry,
+    ) -> Result<UpsertOutcome>;
+    async fn upsert_batch_of_stock_bars(
+        &self,
+        timeframe: Timeframe,
+        spec: &FeatureSpec,
+        model_entries: &[StockBarModelEntry],
+    ) -> Result<UpsertReport>;
+    /// Adds any feature column of `spec` missing from the timeframe's table and
+    /// returns the statements that were applied.
+    async fn sync_feature_columns(
+        &self,
+        timeframe: Timeframe,
+        spec: &FeatureSpec,
+    ) -> Result<Vec<String>>;
+}
+
+impl BarRepository for SqliteDb {
+    async fn insert_stock_bar(
+        &self,
+        timeframe: Timeframe,
+        spec: &FeatureSpec,
+        model_entry: &StockBarModelEntry,
+    ) -> Result<()> {
+        check_features(timeframe, spec, model_entry)?;
+        let columns = spec.table_columns();
+        let sql = format!(
+            "{} VALUES ({})",
+            insert_prefix(timeframe.table(), &columns),
+            vec!["?"; columns.len()].join(", ")
+        );
+        let mut conn = self.pool.acquire().await?;
+        register_bar_symbols(&mut conn, std::slice::from_ref(model_entry)).await?;
+        bind_stock_bar(sqlx::query(&sql), model_entry)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn insert_batch_of_stock_bars(
+        &self,
+        timeframe: Timeframe,
+        spec: &FeatureSpec,
+        model_entries: &[StockBarModelEntry],
+    ) -> Result<()> {
+        for model_entry in model_entries {
+            check_features(timeframe, spec, model_entry)?;
+        }
+        let columns = spec.table_columns();
+
+        // Dropping the transaction on error rolls back the whole batch
+        let mut transaction = self.pool.begin().await?;
+        register_bar_symbols(&mut transaction, model_entries).await?;
+        for chunk in model_entries.chunks(rows_per_statement(columns.len())) {
+            let mut query_builder = QueryBuilder::new(insert_prefix(timeframe.table(), &columns));
+            query_builder.push_values(chunk, push_stock_bar);
+            query_builder.build().execute(&mut *transaction).await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    async fn upsert_stock_bar(
+        &self,
+        timeframe: Timeframe,
+        spec: &FeatureSpec,
+        model_entry: &StockBarModelEntry,
+    ) -> Result<UpsertOutcome> {
+        check_features(timeframe, spec, model_entry)?;
+        let sql = upsert_sql(timeframe.table(), &spec.table_columns());
+        let mut conn = self.pool.acquire().await?;
+        register_bar_symbols(&mut conn, std::slice::from_ref(model_entry)).await?;
+        let max_id = max_stock_bar_id(&mut conn, timeframe.table()).await?;
+        upsert_stock_bar_on(&mut conn, &sql, max_id, model_entry).await
+    }
+
+    async fn upsert_batch_of_stock_bars(
+        &self,
+        timeframe: Timeframe,
+        spec: &FeatureSpec,
+        model_entries: &[StockBarModelEntry],
+    ) -> Result<UpsertReport> {
+        for model_entry in model_entries {
+            check_features(timeframe, spec, model_entry)?;
+        }
+        let columns = spec.table_columns();
+        let clause = upsert_clause(&columns);
+
+        let mut report = UpsertReport::default();
+        let mut transaction = self.pool.begin().await?;
+        register_bar_symbols(&mut transaction, model_entries).await?;
+        let max_id = max_stock_bar_id(&mut transaction, timeframe.table()).await?;
+        let model_entries = last_entry_per_key(model_entries);
+        for chunk in model_entries.chunks(rows_per_statement(columns.len())) {
+            let mut query_builder = QueryBuilder::new(insert_prefix(timeframe.table(), &columns));
+            query_builder.push_values(chunk.iter().copied(), push_stock_bar);
+            query_builder.push(&clause);
+            let returned_ids: Vec<i64> = query_builder
+                .build_query_scalar()
+                .fetch_all(&mut *transaction)
+                .await?;
+
+            // Rows left unchanged by the update return no id
+            for id in &returned_ids {
+                report.record(upsert_outcome(Some(*id), max_id));
+            }
+            report.unchanged += chunk.len().saturating_sub(returned_ids.len());
+        }
+        transaction.commit().await?;
+        Ok(report)
+    }
+
+    async fn sync_feature_columns(
+        &self,
+        timeframe: Timeframe,
+        spec: &FeatureSpec,
+    ) -> Result<Vec<String>> {
+        let existing_columns = self.table_columns(timeframe.table()).await?;
+        let statements = spec.add_columns_sql(timeframe.table(), &existing_columns);
+        for statement in &statements {
+            sqlx::query(statement).execute(&self.pool).await?;
+        }
+        Ok(statements)
+    }
+}
+
+fn check_features(
+    timeframe: Timeframe,
+    spec: &FeatureSpec,
+    model_entry: &StockBarModelEntry,
+) -> Result<()> {
+    let expected = spec.len();
+    if model_entry.features.len() != expected {
+        bail!(
+            "{} bar for {} has {} features, expected {}",
+            timeframe.table(),
+            model_entry.stock_symbol,
+            model_entry.features.len(),
+            expected
+        );
+    }
+    Ok(())
+}
+
+/// Upserts a bar in one statement. `max_id` is the table's highest `id` before
+/// the batch started.
+async fn upsert_stock_bar_on(
+    conn: &mut SqliteConnection,
+    sql: &str,
+    max_id: i64,
+    model_entry: &StockBarModelEntry,
+) -> Result<UpsertOutcome> {
+    let returned_id = bind_stock_bar(sqlx::query(sql), model_entry)
+        .fetch_optional(&mut *conn)
+        .await?
+        .map(|row| row.try_get::<i64, _>("id"))
+        .transpose()?;
+
+    Ok(upsert_outcome(returned_id, max_id))
+}
+
+fn push_stock_bar<'args>(
+    mut row: Separated<'_, 'args, Sqlite, &'static str>,
+    model_entry: &'args StockBarModelEntry,
+) {
+    row.push_bind(&model_entry.event_datetime)
+        .push_bind(model_entry.event_unix_timestamp)
+        .push_bind(model_entry.open_price)
+        .push_bind(model_entry.close_price)
+        .push_bind(model_entry.high_price)
+        .push_bind(model_entry.low_price)
+        .push_bind(model_entry.volume)
+        .push_bind(model_entry.volume_weighted_price)
+        .push_bind(&model_entry.stock_symbol)
+        .push_bind(&model_entry.timeframe)
+        .push_bind(&model_entry.bar_trend)
+        .push_bind(model_entry.buy_or_sell)
+        .push_bind(model_entry.next_period_price)
+        .push_bind(&model_entry.next_period_trend)
+        .push_bind(model_entry.next_period_unix_timestamp)
+        .push_bind(&model_entry.next_period_event_datetime)
+        .push_bind(&model_entry.previous_period_trend)
+        .push_bind(&model_entry.adjustment_mode)
+        .push_bind(&model_entry.provenance);
+    for feature in &model_entry.features {
+        row.push_bind(*feature);
+    }
+}
+
+fn bind_stock_bar<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    model_entry: &'q StockBarModelEntry,
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    let mut query = query
+        .bind(&model_entry.event_datetime)
+        .bind(model_entry.event_unix_timestamp)
+        .bind(model_entry.open_price)
+        .bind(model_entry.close_price)
+        .bind(model_entry.high_price)
+        .bind(model_entry.low_price)
+        .bind(model_entry.volume)
+        .bind(model_entry.volume_weighted_price)
+        .bind(&model_entry.stock_symbol)
+        .bind(&model_entry.timeframe)
+        .bind(&model_entry.bar_trend)
+        .bind(model_entry.buy_or_sell)
+        .bind(model_entry.next_period_price)
+        .bind(&model_entry.next_period_trend)
+        .bind(model_entry.next_period_unix_timestamp)
+        .bind(&model_entry.next_period_event_datetime)
+        .bind(&model_entry.previous_period_trend)
+        .bind(&model_entry.adjustment_mode)
+        .bind(&model_entry.provenance);
+    for feature in &model_entry.features {
+        query = query.bind(*feature);
+    }
+    query
+}
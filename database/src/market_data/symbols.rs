@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use sqlx::SqliteConnection;
+
+use super::stock_bars::StockBarModelEntry;
+use crate::SqliteDb;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum AssetClass {
+    #[default]
+    Stock,
+    Etf,
+}
+
+impl AssetClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AssetClass::Stock => "stock",
+            AssetClass::Etf => "etf",
+        }
+    }
+}
+
+/// Reference data of a ticker. Every bar table references it by
+/// `stock_symbol`.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct Symbol {
+    pub ticker: String,
+    pub name: Option<String>,
+    pub asset_class: AssetClass,
+    /// GICS sector, e.g. "Information Technology".
+    pub sector: Option<String>,
+    /// ETF the ticker is measured against, usually its sector ETF.
+    pub benchmark_etf: Option<String>,
+    pub exchange: Option<String>,
+    /// False once delisted.
+    pub active: bool,
+    /// UTC day of the oldest and newest stored bar across the bar tables.
+    pub first_bar_date: Option<NaiveDate>,
+    pub last_bar_date: Option<NaiveDate>,
+}
+
+/// A ticker from a reference file. Fields left out keep their stored value.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SymbolReference {
+    #[serde(alias = "symbol")]
+    pub ticker: String,
+    pub name: Option<String>,
+    pub asset_class: Option<AssetClass>,
+    pub sector: Option<String>,
+    pub benchmark_etf: Option<String>,
+    pub exchange: Option<String>,
+    pub active: Option<bool>,
+}
+
+const SYMBOL_COLUMNS: &str = "ticker, name, asset_class, sector, benchmark_etf, exchange, active, first_bar_date, last_bar_date";
+
+impl SqliteDb {
+    /// Adds or updates the tickers and returns how many were written.
+    pub async fn upsert_symbols(&self, symbols: &[SymbolReference]) -> Result<u64> {
+        let mut transaction = self.pool.begin().await?;
+        let mut rows = 0;
+        for symbol in symbols {
+            let ticker = symbol.ticker.trim().to_uppercase();
+            sqlx::query("INSERT OR IGNORE INTO symbols (ticker) VALUES (?)")
+                .bind(&ticker)
+                .execute(&mut *transaction)
+                .await?;
+            let result = sqlx::query(
+                "UPDATE symbols SET
+                name = COALESCE(?, name),
+                asset_class = COALESCE(?, asset_class),
+                sector = COALESCE(?, sector),
+                benchmark_etf = COALESCE(?, benchmark_etf),
+                exchange = COALESCE(?, exchange),
+                active = COALESCE(?, active)
+                WHERE ticker = ?",
+            )
+            .bind(&symbol.name)
+            .bind(symbol.asset_class)
+            .bind(&symbol.sector)
+            .bind(symbol.benchmark_etf.as_ref().map(|etf| etf.to_uppercase()))
+            .bind(&symbol.exchange)
+            .bind(symbol.active)
+            .bind(&ticker)
+            .execute(&mut *transaction)
+            .await?;
+            rows += result.rows_affected();
+        }
+        transaction.commit().await?;
+        Ok(rows)
+    }
+
+    pub async fn symbol(&self, ticker: &str) -> Result<Option<Symbol>> {
+        let symbol = sqlx::query_as(&format!(
+            "SELECT {} FROM symbols WHERE ticker = ?",
+            SYMBOL_COLUMNS
+        ))
+        .bind(ticker)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(symbol)
+    }
+
+    /// Every ticker, or those of one sector, alphabetically.
+    pub async fn symbols(&self, sector: Option<&str>) -> Result<Vec<Symbol>> {
+        let symbols = sqlx::query_as(&format!(
+            "SELECT {} FROM symbols WHERE ? IS NULL OR sector = ? ORDER BY ticker",
+            SYMBOL_COLUMNS
+        ))
+        .bind(sector)
+        .bind(sector)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(symbols)
+    }
+}
+
+/// Adds the tickers of a batch of bars missing from `symbols`, so the bars
+/// satisfy the foreign key, and widens their first and last bar dates.
+pub(crate) async fn register_bar_symbols(
+    conn: &mut SqliteConnection,
+    model_entries: &[StockBarModelEntry],
+) -> Result<()> {
+    // event_datetime is "YYYY-MM-DD HH:MM:SS" in UTC
+    let mut bar_dates: BTreeMap<&str, (&str, &str)> = BTreeMap::new();
+    for model_entry in model_entries {
+        let day = model_entry
+            .event_datetime
+            .get(..10)
+            .unwrap_or(&model_entry.event_datetime);
+        bar_dates
+            .entry(&model_entry.stock_symbol)
+            .and_modify(|(first, last)| {
+                *first = (*first).min(day);
+                *last = (*last).max(day);
+            })
+            .or_insert((day, day));
+    }
+
+    for (ticker, (first, last)) in bar_dates {
+        sqlx::query(
+            "INSERT INTO symbols (ticker, first_bar_date, last_bar_date) VALUES (?, ?, ?)
+            ON CONFLICT (ticker) DO UPDATE SET
+            first_bar_date = MIN(COALESCE(first_bar_date, excluded.first_bar_date), excluded.first_bar_date),
+            last_bar_date = MAX(COALESCE(last_bar_date, excluded.last_bar_date), excluded.last_bar_date)",
+        )
+        .bind(ticker)
+        .bind(first)
+        .bind(last)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::daily_entry;
+    use crate::{BarRepository, Timeframe};
+
+    #[tokio::test]
+    async fn test_bars_register_their_symbols() {
+        let db = SqliteDb::in_memory().await;
+        let spec = Timeframe::Daily.feature_spec();
+        let entries = [
+            daily_entry("NEWCO", "2024-03-05 05:00:00", 10.5),
+            daily_entry("NEWCO", "2024-03-04 05:00:00", 10.5),
+        ];
+
+        db.insert_batch_of_stock_bars(Timeframe::Daily, &spec, &entries)
+            .await
+            .unwrap();
+        let newco = db.symbol("NEWCO").await.unwrap().unwrap();
+
+        assert_eq!(newco.asset_class, AssetClass::Stock);
+        assert!(newco.active);
+        assert_eq!(newco.first_bar_date, NaiveDate::from_ymd_opt(2024, 3, 4));
+        assert_eq!(newco.last_bar_date, NaiveDate::from_ymd_opt(2024, 3, 5));
+    }
+
+    #[tokio::test]
+    async fn test_later_bars_widen_the_bar_dates() {
+        let db = SqliteDb::in_memory().await;
+        let spec = Timeframe::Daily.feature_spec();
+        let first = [daily_entry("NEWCO", "2024-03-05 05:00:00", 10.5)];
+        let second = [
+            daily_entry("NEWCO", "2024-03-04 05:00:00", 10.5),
+            daily_entry("NEWCO", "2024-03-06 05:00:00", 10.5),
+        ];
+
+        db.insert_batch_of_stock_bars(Timeframe::Daily, &spec, &first)
+            .await
+            .unwrap();
+        db.insert_batch_of_stock_bars(Timeframe::Daily, &spec, &second)
+            .await
+            .unwrap();
+        let newco = db.symbol("NEWCO").await.unwrap().unwrap();
+
+        assert_eq!(newco.first_bar_date, NaiveDate::from_ymd_opt(2024, 3, 4));
+        assert_eq!(newco.last_bar_date, NaiveDate::from_ymd_opt(2024, 3, 6));
+    }
+
+    #[tokio::test]
+    async fn test_upsert_symbols_keeps_fields_left_out() {
+        let db = SqliteDb::in_memory().await;
+        let reference = |name: Option<&str>| SymbolReference {
+            ticker: "newco".to_string(),
+            name: name.map(str::to_string),
+            asset_class: None,
+            sector: Some("Energy".to_string()),
+            benchmark_etf: Some("xle".to_string()),
+            exchange: Some("NYSE".to_string()),
+            active: None,
+        };
+
+        db.upsert_symbols(&[reference(Some("New Co"))])
+            .await
+            .unwrap();
+        db.upsert_symbols(&[reference(None)]).await.unwrap();
+        let newco = db.symbol("NEWCO").await.unwrap().unwrap();
+        let xlf = db.symbol("XLF").await.unwrap().unwrap();
+        let energy = db.symbols(Some("Energy")).await.unwrap();
+
+        assert_eq!(newco.name.as_deref(), Some("New Co"));
+        assert_eq!(newco.benchmark_etf.as_deref(), Some("XLE"));
+        assert_eq!(newco.first_bar_date, None);
+        assert_eq!(xlf.asset_class, AssetClass::Etf);
+        assert!(energy.iter().any(|symbol| symbol.ticker == "XOM"));
+        assert!(energy.iter().any(|symbol| symbol.ticker == "NEWCO"));
+    }
+}
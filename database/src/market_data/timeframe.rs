@@ -0,0 +1,169 @@
+use alpaca_api_client::TimeFrame;
+
+use super::feature_spec::{FeatureSpec, PriceField};
+
+/// Bar resolution. Every timeframe has its own table made of the shared OHLCV
+/// core columns plus the indicator columns of its `FeatureSpec`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Timeframe {
+    Monthly,
+    Weekly,
+    Daily,
+    Hourly,
+    FifteenMin,
+}
+
+impl Timeframe {
+    pub const ALL: [Timeframe; 5] = [
+        Timeframe::Monthly,
+        Timeframe::Weekly,
+        Timeframe::Daily,
+        Timeframe::Hourly,
+        Timeframe::FifteenMin,
+    ];
+
+    pub fn table(&self) -> &'static str {
+        match self {
+            Timeframe::Monthly => "monthly_stock_bars",
+            Timeframe::Weekly => "weekly_stock_bars",
+            Timeframe::Daily => "daily_stock_bars",
+            Timeframe::Hourly => "hourly_stock_bars",
+            Timeframe::FifteenMin => "fifteen_minute_stock_bars",
+        }
+    }
+
+    pub fn from_table(table: &str) -> Option<Timeframe> {
+        Timeframe::ALL
+            .into_iter()
+            .find(|timeframe| timeframe.table() == table)
+    }
+
+    /// Bar size requested from Alpaca.
+    pub fn alpaca_timeframe(&self) -> TimeFrame {
+        match self {
+            Timeframe::Monthly => TimeFrame::OneMonth,
+            Timeframe::Weekly => TimeFrame::OneWeek,
+            Timeframe::Daily => TimeFrame::OneDay,
+            Timeframe::Hourly => TimeFrame::OneHour,
+            Timeframe::FifteenMin => TimeFrame::FifteenMinutes,
+        }
+    }
+
+    /// Default indicator columns stored for this timeframe.
+    pub fn feature_spec(&self) -> FeatureSpec {
+        use PriceField::{Close, High, Low};
+
+        match self {
+            Timeframe::Monthly => FeatureSpec::new()
+                .sma("twelve_month_sma", Close, 12)
+                .sma("twenty_four_month_sma", Close, 24)
+                .ema("twelve_month_ema", Close, 12)
+                .rsi("twelve_month_rsi", Close, 12)
+                .high("twelve_month_high", High, 12)
+                .low("twelve_month_low", Low, 12)
+                .high("six_month_high", High, 6)
+                .low("six_month_low", Low, 6),
+            Timeframe::Weekly => FeatureSpec::new()
+                .sma("ten_week_sma", Close, 10)
+                .ema("ten_week_ema", Close, 10)
+                .rsi("ten_week_rsi", Close, 10)
+                .high("ten_week_high", High, 10)
+                .low("ten_week_low", Low, 10)
+                .high("five_week_high", High, 5)
+                .low("five_week_low", Low, 5),
+            Timeframe::Daily => FeatureSpec::new()
+                .sma("hundred_day_sma", Close, 100)
+                .ema("hundred_day_ema", Close, 100)
+                .sma("fifty_day_sma", Close, 50)
+                .ema("fifty_day_ema", Close, 50)
+                .sma("twenty_day_sma", Close, 20)
+                .ema("twenty_day_ema", Close, 20)
+                .sma("nine_day_sma", Close, 9)
+                .ema("nine_day_ema", Close, 9)
+                .high("hundred_day_high", High, 100)
+                .low("hundred_day_low", Low, 100)
+                .high("fifty_day_high", High, 50)
+                .low("fifty_day_low", Low, 50)
+                .high("ten_day_high", High, 10)
+                .low("ten_day_low", Low, 10)
+                .rsi("fourteen_day_rsi", Close, 14)
+                .bollinger_bands(
+                    [
+                        "top_bollinger_band",
+                        "middle_bollinger_band",
+                        "bottom_bollinger_band",
+                    ],
+                    Close,
+                    20,
+                    2.0,
+                )
+                .macd_signal("macd_signal", Close),
+            Timeframe::Hourly => FeatureSpec::new()
+                .sma("five_period_sma", Close, 5)
+                .sma("eight_period_sma", Close, 8)
+                .sma("thirteen_period_sma", Close, 13)
+                .rsi("nine_period_rsi", Close, 9)
+                .bollinger_bands(
+                    [
+                        "top_bollinger_band",
+                        "middle_bollinger_band",
+                        "bottom_bollinger_band",
+                    ],
+                    Close,
+                    13,
+                    3.0,
+                )
+                .high("twenty_period_high", High, 20)
+                .low("twenty_period_low", Low, 20)
+                .high("eight_period_high", High, 8)
+                .low("eight_period_low", Low, 8)
+                .high("five_period_high", High, 5)
+                .low("five_period_low", Low, 5),
+            Timeframe::FifteenMin => FeatureSpec::new()
+                .sma("five_period_sma", Close, 5)
+                .sma("eight_period_sma", Close, 8)
+                .sma("thirteen_period_sma", Close, 13)
+                .ema("twenty_period_ema", Close, 20)
+                .rsi("nine_period_rsi", Close, 9)
+                .bollinger_bands(
+                    [
+                        "top_bollinger_band",
+                        "middle_bollinger_band",
+                        "bottom_bollinger_band",
+                    ],
+                    Close,
+                    13,
+                    3.0,
+                )
+                .high("twenty_period_high", High, 20)
+                .low("twenty_period_low", Low, 20)
+                .high("eight_period_high", High, 8)
+                .low("eight_period_low", Low, 8)
+                .high("five_period_high", High, 5)
+                .low("five_period_low", Low, 5),
+        }
+    }
+}
+
+/// Columns shared by every bar table, in insert order.
+pub const CORE_COLUMNS: [&str; 19] = [
+    "event_datetime",
+    "event_unix_timestamp",
+    "open_price",
+    "close_price",
+    "high_price",
+    "low_price",
+    "volume",
+    "volume_weighted_price",
+    "stock_symbol",
+    "timeframe",
+    "bar_trend",
+    "buy_or_sell",
+    "next_period_price",
+    "next_period_trend",
+    "next_period_unix_timestamp",
+    "next_period_event_datetime",
+    "previous_period_trend",
+    "adjustment_mode",
+    "provenance",
+];
@@ -0,0 +1,201 @@
+use std::{collections::HashSet, fmt};
+
+use anyhow::Result;
+use sqlx::SqliteConnection;
+
+use super::batch::insert_prefix;
+use super::stock_bars::StockBarModelEntry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertOutcome {
+    Inserted,
+    Updated,
+    Unchanged,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct UpsertReport {
+    pub inserted: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+}
+
+impl UpsertReport {
+    pub fn record(&mut self, outcome: UpsertOutcome) {
+        match outcome {
+            UpsertOutcome::Inserted => self.inserted += 1,
+            UpsertOutcome::Updated => self.updated += 1,
+            UpsertOutcome::Unchanged => self.unchanged += 1,
+        }
+    }
+}
+
+impl fmt::Display for UpsertReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} inserted, {} updated, {} unchanged",
+            self.inserted, self.updated, self.unchanged
+        )
+    }
+}
+
+/// Builds an `INSERT ... ON CONFLICT DO UPDATE` of one row for a bar table.
+pub(crate) fn upsert_sql(table: &str, columns: &[&str]) -> String {
+    format!(
+        "{}VALUES ({}){}",
+        insert_prefix(table, columns),
+        vec!["?"; columns.len()].join(", "),
+        upsert_clause(columns)
+    )
+}
+
+/// The `ON CONFLICT` clause following the `VALUES` of an insert into a bar
+/// table keyed on `(stock_symbol, timeframe, event_unix_timestamp)`. The update
+/// only fires when a column actually differs, so only inserted and updated rows
+/// return their `id`.
+pub(crate) fn upsert_clause(columns: &[&str]) -> String {
+    let updated_columns: Vec<&str> = columns
+        .iter()
+        .copied()
+        .filter(|column| !is_key_column(column))
+        .collect();
+
+    let assignments = updated_columns
+        .iter()
+        .map(|column| format!("{0} = excluded.{0}", column))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let changes = updated_columns
+        .iter()
+        .map(|column| format!("{0} IS NOT excluded.{0}", column))
+        .collect::<Vec<_>>()
+        .join(" OR ");
+
+    format!(
+        " ON CONFLICT (stock_symbol, timeframe, event_unix_timestamp) \
+         DO UPDATE SET {} WHERE {} RETURNING id",
+        assignments, changes
+    )
+}
+
+/// Highest `id` of a bar table. Updates keep a row's `id` and inserts get a
+/// higher one, so an upsert returning an `id` above it inserted the row.
+pub(crate) async fn max_stock_bar_id(conn: &mut SqliteConnection, table: &str) -> Result<i64> {
+    let max_id = sqlx::query_scalar(&format!("SELECT COALESCE(MAX(id), 0) FROM {}", table))
+        .fetch_one(&mut *conn)
+        .await?;
+
+    Ok(max_id)
+}
+
+pub(crate) fn upsert_outcome(returned_id: Option<i64>, max_id_before: i64) -> UpsertOutcome {
+    match returned_id {
+        None => UpsertOutcome::Unchanged,
+        Some(id) if id > max_id_before => UpsertOutcome::Inserted,
+        Some(_) => UpsertOutcome::Updated,
+    }
+}
+
+/// The last entry of every `(stock_symbol, timeframe, event_unix_timestamp)`
+/// key, in batch order. Later duplicates overwrite earlier ones anyway, and
+/// upserting each key once keeps a row from being counted twice.
+pub(crate) fn last_entry_per_key(model_entries: &[StockBarModelEntry]) -> Vec<&StockBarModelEntry> {
+    let mut seen = HashSet::new();
+    let mut entries: Vec<&StockBarModelEntry> = model_entries
+        .iter()
+        .rev()
+        .filter(|entry| {
+            seen.insert((
+                entry.stock_symbol.as_str(),
+                entry.timeframe.as_str(),
+                entry.event_unix_timestamp,
+            ))
+        })
+        .collect();
+    entries.reverse();
+    entries
+}
+
+fn is_key_column(column: &str) -> bool {
+    matches!(
+        column,
+        "stock_symbol" | "timeframe" | "event_unix_timestamp"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::daily_entry;
+    use crate::{BarRepository, SqliteDb, Timeframe};
+
+    #[tokio::test]
+    async fn test_upsert_reports_each_outcome() {
+        let db = SqliteDb::in_memory().await;
+        let spec = Timeframe::Daily.feature_spec();
+        let first = [
+            daily_entry("AAPL", "2024-03-04 05:00:00", 10.5),
+            daily_entry("AAPL", "2024-03-05 05:00:00", 10.5),
+        ];
+        let second = [
+            daily_entry("AAPL", "2024-03-04 05:00:00", 10.5),
+            daily_entry("AAPL", "2024-03-05 05:00:00", 12.0),
+            daily_entry("AAPL", "2024-03-06 05:00:00", 10.5),
+        ];
+
+        let inserted = db
+            .upsert_batch_of_stock_bars(Timeframe::Daily, &spec, &first)
+            .await
+            .unwrap();
+        let mixed = db
+            .upsert_batch_of_stock_bars(Timeframe::Daily, &spec, &second)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            (inserted.inserted, inserted.updated, inserted.unchanged),
+            (2, 0, 0)
+        );
+        assert_eq!((mixed.inserted, mixed.updated, mixed.unchanged), (1, 1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_upsert_counts_duplicate_keys_once() {
+        let db = SqliteDb::in_memory().await;
+        let spec = Timeframe::Daily.feature_spec();
+        let duplicated = [
+            daily_entry("AAPL", "2024-03-04 05:00:00", 10.5),
+            daily_entry("AAPL", "2024-03-04 05:00:00", 12.0),
+            daily_entry("MSFT", "2024-03-04 05:00:00", 10.5),
+        ];
+        let repeated = [
+            daily_entry("AAPL", "2024-03-04 05:00:00", 12.0),
+            daily_entry("AAPL", "2024-03-04 05:00:00", 12.0),
+        ];
+
+        let inserted = db
+            .upsert_batch_of_stock_bars(Timeframe::Daily, &spec, &duplicated)
+            .await
+            .unwrap();
+        let unchanged = db
+            .upsert_batch_of_stock_bars(Timeframe::Daily, &spec, &repeated)
+            .await
+            .unwrap();
+        let stored = db
+            .latest_bar("AAPL", Timeframe::Daily)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            (inserted.inserted, inserted.updated, inserted.unchanged),
+            (2, 0, 0)
+        );
+        assert_eq!(
+            (unchanged.inserted, unchanged.updated, unchanged.unchanged),
+            (0, 0, 1)
+        );
+        assert_eq!(stored.close_price, 12.0);
+    }
+}
@@ -0,0 +1,154 @@
+use anyhow::{anyhow, bail, Result};
+
+use crate::SqliteDb;
+
+/// A named list of symbols, e.g. the holdings of a sector ETF.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct Watchlist {
+    pub name: String,
+    pub description: Option<String>,
+    /// Number of symbols on the list.
+    pub members: i64,
+}
+
+impl SqliteDb {
+    /// Every watchlist, by name.
+    pub async fn watchlists(&self) -> Result<Vec<Watchlist>> {
+        let watchlists = sqlx::query_as(
+            "SELECT watchlists.name, watchlists.description, COUNT(watchlist_members.stock_symbol) AS members
+            FROM watchlists
+            LEFT JOIN watchlist_members ON watchlist_members.watchlist_id = watchlists.id
+            GROUP BY watchlists.id
+            ORDER BY watchlists.name",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(watchlists)
+    }
+
+    pub async fn create_watchlist(&self, name: &str, description: Option<&str>) -> Result<()> {
+        if name.trim().is_empty() {
+            bail!("Watchlist name cannot be empty");
+        }
+
+        let result = sqlx::query(
+            "INSERT INTO watchlists (name, description) VALUES (?, ?) ON CONFLICT (name) DO NOTHING",
+        )
+        .bind(name)
+        .bind(description)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            bail!("Watchlist already exists: {}", name);
+        }
+        Ok(())
+    }
+
+    /// Adds symbols to a watchlist, ignoring those already on it. Returns how
+    /// many were added.
+    pub async fn add_watchlist_symbols(&self, name: &str, symbols: &[String]) -> Result<u64> {
+        let id = self.watchlist_id(name).await?;
+        let mut transaction = self.pool.begin().await?;
+        let mut rows = 0;
+        for symbol in symbols {
+            let result = sqlx::query(
+                "INSERT INTO watchlist_members (watchlist_id, stock_symbol) VALUES (?, ?)
+                ON CONFLICT (watchlist_id, stock_symbol) DO NOTHING",
+            )
+            .bind(id)
+            .bind(symbol.to_uppercase())
+            .execute(&mut *transaction)
+            .await?;
+            rows += result.rows_affected();
+        }
+        transaction.commit().await?;
+        Ok(rows)
+    }
+
+    /// Removes symbols from a watchlist and returns how many were on it.
+    pub async fn remove_watchlist_symbols(&self, name: &str, symbols: &[String]) -> Result<u64> {
+        let id = self.watchlist_id(name).await?;
+        let mut transaction = self.pool.begin().await?;
+        let mut rows = 0;
+        for symbol in symbols {
+            let result = sqlx::query(
+                "DELETE FROM watchlist_members WHERE watchlist_id = ? AND stock_symbol = ?",
+            )
+            .bind(id)
+            .bind(symbol.to_uppercase())
+            .execute(&mut *transaction)
+            .await?;
+            rows += result.rows_affected();
+        }
+        transaction.commit().await?;
+        Ok(rows)
+    }
+
+    /// Symbols on a watchlist, alphabetically.
+    pub async fn watchlist_symbols(&self, name: &str) -> Result<Vec<String>> {
+        let id = self.watchlist_id(name).await?;
+        let symbols = sqlx::query_scalar(
+            "SELECT stock_symbol FROM watchlist_members WHERE watchlist_id = ? ORDER BY stock_symbol",
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(symbols)
+    }
+
+    /// Symbols on any watchlist, alphabetically.
+    pub async fn all_watchlist_symbols(&self) -> Result<Vec<String>> {
+        let symbols = sqlx::query_scalar(
+            "SELECT DISTINCT stock_symbol FROM watchlist_members ORDER BY stock_symbol",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(symbols)
+    }
+
+    async fn watchlist_id(&self, name: &str) -> Result<i64> {
+        sqlx::query_scalar("SELECT id FROM watchlists WHERE name = ?")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| anyhow!("Unknown watchlist: {}", name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_seeded_and_edited_watchlists() {
+        let db = SqliteDb::in_memory().await;
+
+        let tech = db.watchlist_symbols("tech").await.unwrap();
+        db.create_watchlist("banks", Some("Large US banks"))
+            .await
+            .unwrap();
+        let duplicate = db.create_watchlist("banks", None).await;
+        let symbols = ["jpm".to_string(), "BAC".to_string(), "WFC".to_string()];
+        let added = db.add_watchlist_symbols("banks", &symbols).await.unwrap();
+        let added_again = db.add_watchlist_symbols("banks", &symbols).await.unwrap();
+        let removed = db
+            .remove_watchlist_symbols("banks", &["WFC".to_string()])
+            .await
+            .unwrap();
+        let banks = db.watchlist_symbols("banks").await.unwrap();
+        let watchlists = db.watchlists().await.unwrap();
+        let unknown = db.watchlist_symbols("missing").await;
+
+        assert!(tech.contains(&"AAPL".to_string()));
+        assert!(duplicate.is_err());
+        assert_eq!((added, added_again, removed), (3, 0, 1));
+        assert_eq!(banks, ["BAC", "JPM"]);
+        assert!(watchlists
+            .iter()
+            .any(|watchlist| watchlist.name == "banks" && watchlist.members == 2));
+        assert!(unknown.is_err());
+    }
+}
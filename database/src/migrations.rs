@@ -0,0 +1,316 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Result};
+use sqlx::{
+    migrate::{Migrate, Migration, Migrator},
+    Connection, SqliteConnection, SqliteExecutor,
+};
+
+use crate::{SqliteDb, Timeframe};
+
+/// Schema migrations from `database/migrations`, embedded at compile time.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// An embedded migration and whether the database has applied it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub applied: bool,
+    /// Applied from a different revision of the file than the embedded one.
+    pub checksum_mismatch: bool,
+    /// Has a down script, so `revert_migration` can undo it.
+    pub reversible: bool,
+}
+
+impl SqliteDb {
+    /// Every embedded migration, oldest first.
+    pub async fn migration_status(&self) -> Result<Vec<MigrationStatus>> {
+        let mut conn = self.pool.acquire().await?;
+        conn.ensure_migrations_table().await?;
+        let applied: HashMap<i64, Vec<u8>> = conn
+            .list_applied_migrations()
+            .await?
+            .into_iter()
+            .map(|migration| (migration.version, migration.checksum.into_owned()))
+            .collect();
+
+        let reversible: HashSet<i64> = MIGRATOR
+            .iter()
+            .filter(|migration| migration.migration_type.is_down_migration())
+            .map(|migration| migration.version)
+            .collect();
+
+        Ok(MIGRATOR
+            .iter()
+            .filter(|migration| !migration.migration_type.is_down_migration())
+            .map(|migration| {
+                let checksum = applied.get(&migration.version);
+                MigrationStatus {
+                    version: migration.version,
+                    description: migration.description.to_string(),
+                    applied: checksum.is_some(),
+                    checksum_mismatch: checksum
+                        .is_some_and(|checksum| checksum[..] != migration.checksum[..]),
+                    reversible: reversible.contains(&migration.version),
+                }
+            })
+            .collect())
+    }
+
+    /// Applies the pending migrations and returns them.
+    ///
+    /// Some migrations rebuild bar tables by copying a fixed column list, which
+    /// would drop feature columns added by `sync-features`. Migrating fails
+    /// instead while a pending migration would drop such a column.
+    pub async fn migrate(&self) -> Result<Vec<MigrationStatus>> {
+        let pending: Vec<MigrationStatus> = self
+            .migration_status()
+            .await?
+            .into_iter()
+            .filter(|migration| !migration.applied)
+            .collect();
+        let to_apply: Vec<&Migration> = MIGRATOR
+            .iter()
+            .filter(|migration| !migration.migration_type.is_down_migration())
+            .filter(|migration| {
+                pending
+                    .iter()
+                    .any(|pending| pending.version == migration.version)
+            })
+            .collect();
+        self.check_kept_columns(&to_apply).await?;
+        MIGRATOR.run(&self.pool).await?;
+        Ok(pending)
+    }
+
+    /// Fails when running `scripts` in order would drop a column of a bar
+    /// table that the applied migrations do not create. The scripts run on an
+    /// in-memory database holding the schema of the applied migrations plus
+    /// those columns, which must all be left afterwards.
+    async fn check_kept_columns(&self, scripts: &[&Migration]) -> Result<()> {
+        if scripts.is_empty() {
+            return Ok(());
+        }
+        let applied: HashSet<i64> = self
+            .migration_status()
+            .await?
+            .into_iter()
+            .filter(|migration| migration.applied)
+            .map(|migration| migration.version)
+            .collect();
+
+        let mut reference = SqliteConnection::connect("sqlite::memory:").await?;
+        for migration in MIGRATOR
+            .iter()
+            .filter(|migration| !migration.migration_type.is_down_migration())
+            .filter(|migration| applied.contains(&migration.version))
+        {
+            sqlx::raw_sql(&migration.sql)
+                .execute(&mut reference)
+                .await?;
+        }
+
+        let mut added: Vec<(&str, String)> = Vec::new();
+        for table in Timeframe::ALL.iter().map(Timeframe::table) {
+            let known: Vec<String> = column_types(&mut reference, table)
+                .await?
+                .into_iter()
+                .map(|(column, _)| column)
+                .collect();
+            for (column, column_type) in column_types(&self.pool, table).await? {
+                if known.contains(&column) {
+                    continue;
+                }
+                sqlx::raw_sql(&format!(
+                    "ALTER TABLE {} ADD COLUMN {} {}",
+                    table, column, column_type
+                ))
+                .execute(&mut reference)
+                .await?;
+                added.push((table, column));
+            }
+        }
+
+        if added.is_empty() {
+            return Ok(());
+        }
+        for migration in scripts {
+            sqlx::raw_sql(&migration.sql)
+                .execute(&mut reference)
+                .await?;
+            for (table, column) in &added {
+                let kept = column_types(&mut reference, table)
+                    .await?
+                    .into_iter()
+                    .any(|(kept, _)| kept == *column);
+                if !kept {
+                    bail!(
+                        "Migration {} ({}) would drop column {} of {}. Drop it with ALTER TABLE ... DROP COLUMN, migrate, then add it back with `cli database sync-features`",
+                        migration.version,
+                        migration.description,
+                        column,
+                        table
+                    );
+                }
+            }
+        }
+        reference.close().await?;
+        Ok(())
+    }
+
+    /// Reverts the latest applied migration and returns it, or `None` when
+    /// nothing is applied. Migrations without a down script cannot be reverted,
+    /// and like `migrate` it fails while the down script would drop a column.
+    pub async fn revert_migration(&self) -> Result<Option<MigrationStatus>> {
+        let status = self.migration_status().await?;
+        let mut applied = status.iter().rev().filter(|migration| migration.applied);
+        let Some(latest) = applied.next() else {
+            return Ok(None);
+        };
+        if !latest.reversible {
+            bail!(
+                "Migration {} ({}) has no down script and cannot be reverted",
+                latest.version,
+                latest.description
+            );
+        }
+
+        let down: Vec<&Migration> = MIGRATOR
+            .iter()
+            .filter(|migration| migration.migration_type.is_down_migration())
+            .filter(|migration| migration.version == latest.version)
+            .collect();
+        self.check_kept_columns(&down).await?;
+
+        let target = applied.next().map_or(0, |previous| previous.version);
+        MIGRATOR.undo(&self.pool, target).await?;
+        Ok(Some(latest.clone()))
+    }
+}
+
+/// Name and declared type of every column of a table.
+async fn column_types<'e>(
+    executor: impl SqliteExecutor<'e>,
+    table: &str,
+) -> Result<Vec<(String, String)>> {
+    let columns = sqlx::query_as(&format!(
+        "SELECT name, type FROM pragma_table_info('{}')",
+        table
+    ))
+    .fetch_all(executor)
+    .await?;
+
+    Ok(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::daily_entry;
+    use crate::{BarRepository, StockBarsQuery};
+
+    #[tokio::test]
+    async fn test_create_new_applies_every_migration() {
+        let db = SqliteDb::create_new("sqlite::memory:").await.unwrap();
+        let status = db.migration_status().await.unwrap();
+        let pending = db.migrate().await.unwrap();
+
+        assert!(!status.is_empty());
+        assert!(status
+            .iter()
+            .all(|migration| migration.applied && !migration.checksum_mismatch));
+        assert!(pending.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_revert_and_migrate_again_round_trip() {
+        let db = SqliteDb::in_memory().await;
+        let spec = Timeframe::Daily.feature_spec();
+        db.insert_batch_of_stock_bars(
+            Timeframe::Daily,
+            &spec,
+            &[daily_entry("AAPL", "2024-03-04 05:00:00", 10.5)],
+        )
+        .await
+        .unwrap();
+        let migrated = schema(&db).await;
+
+        let mut reverted = Vec::new();
+        for _ in 0..6 {
+            reverted.push(db.revert_migration().await.unwrap().unwrap().version);
+        }
+        let irreversible = db.revert_migration().await.unwrap_err();
+        let symbols_columns = db.table_columns("symbols").await.unwrap();
+        db.migrate().await.unwrap();
+        let bars = StockBarsQuery::new(Timeframe::Daily)
+            .fetch_all(&db)
+            .await
+            .unwrap();
+
+        assert_eq!(reverted.first(), Some(&20241030000000));
+        assert_eq!(reverted.last(), Some(&20241020000000));
+        assert!(irreversible
+            .to_string()
+            .contains("split weekly and monthly bars"));
+        assert!(symbols_columns.is_empty());
+        assert_eq!(schema(&db).await, migrated);
+        assert_eq!(bars.len(), 1);
+    }
+
+    /// Every column, index and foreign key, one per line.
+    async fn schema(db: &SqliteDb) -> Vec<String> {
+        sqlx::query_scalar(
+            r#"SELECT m.name || '.' || p.name || ' ' || p.type || ' ' || p."notnull" || ' '
+                || IFNULL(p.dflt_value, '') || ' ' || p.pk
+            FROM sqlite_master m JOIN pragma_table_info(m.name) p WHERE m.type = 'table'
+            UNION ALL SELECT 'index ' || name || ' on ' || tbl_name
+            FROM sqlite_master WHERE type = 'index'
+            UNION ALL SELECT m.name || '.' || f."from" || ' references ' || f."table"
+            FROM sqlite_master m JOIN pragma_foreign_key_list(m.name) f WHERE m.type = 'table'
+            ORDER BY 1"#,
+        )
+        .fetch_all(&db.pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_migrate_refuses_to_drop_unknown_columns() {
+        // The next migration, add symbols, rebuilds every bar table
+        let db = SqliteDb::in_memory_at(20241020000000).await;
+        sqlx::query("ALTER TABLE daily_stock_bars ADD COLUMN custom_feature REAL")
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        let error = db.migrate().await.unwrap_err().to_string();
+        let pending = db
+            .migration_status()
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|migration| !migration.applied)
+            .count();
+
+        assert!(error.contains("add symbols"));
+        assert!(error.contains("custom_feature of daily_stock_bars"));
+        assert!(pending > 0);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_keeps_unknown_columns_of_altered_tables() {
+        // The next migration, add bar provenance, only adds a column
+        let db = SqliteDb::in_memory_at(20241028000000).await;
+        sqlx::query("ALTER TABLE daily_stock_bars ADD COLUMN custom_feature REAL")
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        db.migrate().await.unwrap();
+        let columns = db.table_columns("daily_stock_bars").await.unwrap();
+
+        assert!(columns.contains(&"custom_feature".to_string()));
+        assert!(columns.contains(&"provenance".to_string()));
+    }
+}
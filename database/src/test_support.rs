@@ -0,0 +1,79 @@
+//! Fixtures shared by the unit tests.
+
+use chrono::NaiveDate;
+use sqlx::{migrate::Migrate, sqlite::SqlitePoolOptions};
+
+use crate::{SqliteDb, StockBarModelEntry, Timeframe, MIGRATOR};
+
+impl SqliteDb {
+    /// A fresh in-memory database with every migration applied.
+    pub(crate) async fn in_memory() -> Self {
+        Self::in_memory_at(i64::MAX).await
+    }
+
+    /// A fresh in-memory database with the migrations up to `version` applied.
+    /// The pool keeps a single connection open, since each connection to
+    /// `:memory:` opens its own database.
+    pub(crate) async fn in_memory_at(version: i64) -> Self {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        conn.ensure_migrations_table().await.unwrap();
+        for migration in MIGRATOR
+            .iter()
+            .filter(|migration| !migration.migration_type.is_down_migration())
+            .filter(|migration| migration.version <= version)
+        {
+            conn.apply(migration).await.unwrap();
+        }
+        drop(conn);
+
+        Self {
+            uri: "sqlite::memory:".to_string(),
+            pool,
+        }
+    }
+}
+
+/// A daily bar with every default feature set, at `event_datetime` in
+/// "YYYY-MM-DD HH:MM:SS" UTC.
+pub(crate) fn daily_entry(
+    stock_symbol: &str,
+    event_datetime: &str,
+    close: f32,
+) -> StockBarModelEntry {
+    let event_unix_timestamp = NaiveDate::parse_from_str(&event_datetime[..10], "%Y-%m-%d")
+        .unwrap()
+        .and_hms_opt(5, 0, 0)
+        .unwrap()
+        .and_utc()
+        .timestamp_millis();
+    StockBarModelEntry {
+        event_datetime: event_datetime.to_string(),
+        event_unix_timestamp,
+        open_price: 10.0,
+        close_price: close,
+        high_price: 11.0,
+        low_price: 9.5,
+        volume: 1000.0,
+        volume_weighted_price: 10.25,
+        stock_symbol: stock_symbol.to_string(),
+        timeframe: "1Day".to_string(),
+        bar_trend: "Bullish".to_string(),
+        buy_or_sell: 1,
+        next_period_price: 11.0,
+        next_period_trend: "Bullish".to_string(),
+        next_period_unix_timestamp: 0,
+        next_period_event_datetime: String::new(),
+        previous_period_trend: "Bullish".to_string(),
+        adjustment_mode: "raw".to_string(),
+        provenance: "fetched".to_string(),
+        features: vec![None; Timeframe::Daily.feature_spec().len()],
+    }
+}